@@ -0,0 +1,68 @@
+use std::env;
+use std::io;
+
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use shikibetsu::{run_with_writer, Args};
+
+const DIRS: usize = 100;
+const NESTED_DIRS: usize = 20;
+const FILES: usize = 50;
+
+fn synthetic_tree() -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let dirs = env::var("SB_BENCH_DIRS")
+        .ok()
+        .and_then(|dirs| dirs.parse().ok())
+        .unwrap_or(DIRS);
+
+    for dir in 0..dirs {
+        for nested in 0..NESTED_DIRS {
+            let nested_dir = temp.child(format!("dir{dir}/nested{nested}"));
+            nested_dir.create_dir_all().unwrap();
+
+            for file in 0..FILES {
+                nested_dir
+                    .child(format!("file{file}.txt"))
+                    .write_str(&"x".repeat(file))
+                    .unwrap();
+            }
+        }
+    }
+
+    temp
+}
+
+fn recursive_listing(c: &mut Criterion) {
+    let temp = synthetic_tree();
+    let threads = [1, 2, 4, 8, 0];
+    let mut group = c.benchmark_group("recursive_listing");
+
+    for (name, long, total_size) in [
+        ("default", false, false),
+        ("long", true, false),
+        ("total_size", true, true),
+    ] {
+        for threads in threads {
+            let args = Args {
+                paths: vec![temp.path().to_path_buf()],
+                recursive: true,
+                one_per_line: true,
+                long,
+                total_size,
+                threads,
+                ..Args::default()
+            };
+
+            group.bench_with_input(BenchmarkId::new(name, threads), &args, |b, args| {
+                b.iter(|| run_with_writer(args, &mut io::sink()).unwrap())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, recursive_listing);
+criterion_main!(benches);
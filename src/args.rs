@@ -1,9 +1,16 @@
+use std::time::SystemTime;
+
 use clap::Parser;
 
-use crate::entries_handler::SortKey;
+use crate::entries_handler::TimeField;
+use crate::entry::EntryKind;
+use crate::error::ErrorSink;
+use crate::parse::{parse_size, parse_time, parse_user};
+use crate::render::{ColorChoice, OutputFormat, TimeStyle};
+use crate::sort::{GroupDirs, SortKey, SortSpec};
 
 //$[begin_cov_exclude]
-#[derive(Parser)]
+#[derive(Clone, Parser)]
 #[command(
     name = "shikibetsu",
     bin_name = "sb",
@@ -13,14 +20,39 @@ use crate::entries_handler::SortKey;
 )]
 pub struct Args {
     #[arg(default_value = ".")]
-    pub path: std::path::PathBuf,
+    pub paths: Vec<std::path::PathBuf>,
+
+    #[arg(short = '1', long = "oneline", default_value = "false")]
+    pub one_per_line: bool,
+
+    #[arg(long = "allocated", default_value = "false")]
+    pub allocated: bool,
 
     #[arg(short = 'a', long = "all", default_value = "false")]
     pub show_hidden: bool,
 
-    #[arg(short = 'c', long = "created", default_value = "false")]
+    #[arg(
+        short = 'C',
+        long = "grid",
+        default_value = "false",
+        conflicts_with_all = ["one_per_line", "long", "recursive"]
+    )]
+    pub grid: bool,
+
+    #[arg(
+        short = 'c',
+        long = "created",
+        visible_alias = "birth",
+        default_value = "false"
+    )]
     pub show_created_ts: bool,
 
+    #[arg(long = "changed", default_value = "false")]
+    pub show_changed_ts: bool,
+
+    #[arg(long = "color", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
     #[arg(short = 'd', long = "dirs", default_value = "false")]
     pub show_only_dirs: bool,
 
@@ -30,41 +62,189 @@ pub struct Args {
     #[arg(short = 'f', long = "files", default_value = "false")]
     pub show_only_files: bool,
 
+    #[arg(long = "follow", default_value = "false")]
+    pub follow: bool,
+
+    #[arg(long = "format", default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[arg(long = "exclude", value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    #[arg(long = "git-ignore", default_value = "false")]
+    pub git_ignore: bool,
+
+    #[arg(long = "group", default_value = "false")]
+    pub show_group: bool,
+
+    #[arg(long = "group-dirs", value_name = "WHERE", default_value_t = GroupDirs::None)]
+    pub group_dirs: GroupDirs,
+
+    #[arg(short = 'H', long = "links", default_value = "false")]
+    pub show_links: bool,
+
+    #[arg(short = 'i', long = "inode", default_value = "false")]
+    pub show_inode: bool,
+
+    #[arg(long = "include", value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    #[arg(short = 'l', long = "long", default_value = "false")]
+    pub long: bool,
+
+    #[arg(
+        short = 'L',
+        long = "depth",
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "recursive"
+    )]
+    pub depth: Option<u64>,
+
     #[arg(short = 'm', long = "modified", default_value = "false")]
     pub show_modified_ts: bool,
 
+    #[arg(long = "max-size", value_name = "SIZE", value_parser = parse_size)]
+    pub max_size: Option<u64>,
+
+    #[arg(long = "min-size", value_name = "SIZE", value_parser = parse_size)]
+    pub min_size: Option<u64>,
+
+    #[arg(long = "newer", value_name = "TIME", value_parser = parse_time)]
+    pub newer: Option<SystemTime>,
+
+    #[arg(long = "older", value_name = "TIME", value_parser = parse_time)]
+    pub older: Option<SystemTime>,
+
+    #[arg(long = "octal", default_value = "false")]
+    pub show_octal: bool,
+
+    #[arg(long = "owned-by", value_name = "USER", value_parser = parse_user)]
+    pub owned_by: Vec<u32>,
+
+    #[arg(long = "owner", default_value = "false")]
+    pub show_owner: bool,
+
     #[arg(short = 'p', long = "perms", default_value = "false")]
     pub show_permissions: bool,
 
     #[arg(short = 'R', long = "recursive", default_value = "false")]
     pub recursive: bool,
 
+    #[arg(long = "regex", default_value = "false")]
+    pub regex: bool,
+
     #[arg(short = 'r', long = "reverse", default_value = "false")]
     pub reverse: bool,
 
     #[arg(short = 'S', long = "size", default_value = "false")]
     pub show_size: bool,
 
-    #[arg(short = 's', long = "sort", default_value_t = SortKey::Name)]
-    pub sort_by: SortKey,
+    #[arg(
+        short = 's',
+        long = "sort",
+        value_name = "KEY[:asc|:desc][,...]",
+        default_value = "n",
+        value_delimiter = ',',
+        value_parser = str::parse::<SortSpec>
+    )]
+    pub sort_by: Vec<SortSpec>,
+
+    #[arg(short = 't', long = "type", value_delimiter = ',')]
+    pub kinds: Vec<EntryKind>,
+
+    #[arg(long = "total-size", default_value = "false")]
+    pub total_size: bool,
+
+    #[arg(long = "threads", value_name = "N", default_value_t = 0)]
+    pub threads: usize,
+
+    #[arg(long = "time-field", default_value_t = TimeField::Modified)]
+    pub time_field: TimeField,
+
+    #[arg(
+        long = "time-style",
+        value_name = "STYLE",
+        default_value_t = TimeStyle::Relative,
+        value_parser = str::parse::<TimeStyle>
+    )]
+    pub time_style: TimeStyle,
+
+    #[arg(short = 'u', long = "accessed", default_value = "false")]
+    pub show_accessed_ts: bool,
+
+    #[arg(long = "utc", default_value = "false")]
+    pub utc: bool,
+
+    #[arg(
+        short = 'x',
+        long = "across",
+        default_value = "false",
+        conflicts_with_all = ["one_per_line", "long", "recursive"]
+    )]
+    pub across: bool,
+
+    #[arg(long = "ascii", default_value = "false")]
+    pub ascii: bool,
+
+    #[arg(skip)]
+    pub is_terminal: bool,
+
+    #[arg(skip)]
+    pub error_sink: Option<ErrorSink>,
 }
 //$[end_cov_exclude]
 
-impl Args {
-    pub fn default() -> Args {
+impl Default for Args {
+    fn default() -> Args {
         Args {
-            path: std::path::PathBuf::from("."),
+            paths: vec![std::path::PathBuf::from(".")],
+            one_per_line: false,
+            allocated: false,
             show_hidden: false,
+            grid: false,
             show_created_ts: false,
+            color: ColorChoice::Auto,
             show_only_dirs: false,
             show_emoji_icon: false,
             show_only_files: false,
+            follow: false,
+            format: OutputFormat::Text,
+            exclude: vec![],
+            git_ignore: false,
+            show_group: false,
+            show_inode: false,
+            show_links: false,
+            show_accessed_ts: false,
+            show_changed_ts: false,
+            group_dirs: GroupDirs::None,
+            include: vec![],
+            long: false,
+            depth: None,
             show_modified_ts: false,
+            max_size: None,
+            min_size: None,
+            newer: None,
+            older: None,
+            show_octal: false,
+            owned_by: vec![],
+            show_owner: false,
             show_permissions: false,
             recursive: false,
+            regex: false,
             reverse: false,
             show_size: false,
-            sort_by: SortKey::Name,
+            sort_by: vec![SortKey::Name.into()],
+            kinds: vec![],
+            total_size: false,
+            threads: 0,
+            time_field: TimeField::Modified,
+            time_style: TimeStyle::Relative,
+            utc: false,
+            across: false,
+            ascii: false,
+            is_terminal: false,
+            error_sink: None,
         }
     }
 }
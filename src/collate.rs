@@ -0,0 +1,131 @@
+use std::cmp::Ordering;
+use std::ffi::CString;
+
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = Chunks::new(a);
+    let mut b_chunks = Chunks::new(b);
+
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(Chunk::Number(a)), Some(Chunk::Number(b))) => cmp_numbers(a, b),
+            (Some(a), Some(b)) => a.as_str().cmp(b.as_str()),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn cmp_numbers(a: &str, b: &str) -> Ordering {
+    let a_digits = a.trim_start_matches('0');
+    let b_digits = b.trim_start_matches('0');
+
+    a_digits
+        .len()
+        .cmp(&b_digits.len())
+        .then_with(|| a_digits.cmp(b_digits))
+}
+
+// Collates with the process's current LC_COLLATE, which stays "C" unless the
+// embedding program calls setlocale itself (the sb binary does, in main).
+pub(crate) fn locale_key(name: &str) -> Vec<u8> {
+    let Ok(name) = CString::new(name) else {
+        return name.as_bytes().to_vec();
+    };
+
+    let mut key = vec![0u8; name.as_bytes().len() * 4 + 1];
+    loop {
+        // SAFETY: `name` is NUL-terminated and `key` has `key.len()` writable bytes,
+        // which is the most strxfrm writes. When the returned length does not fit,
+        // the buffer contents are unspecified and are discarded by retrying with a
+        // larger buffer.
+        let len = unsafe { libc::strxfrm(key.as_mut_ptr().cast(), name.as_ptr(), key.len()) };
+        if len < key.len() {
+            key.truncate(len);
+            return key;
+        }
+        key.resize(len + 1, 0);
+    }
+}
+
+enum Chunk<'a> {
+    Number(&'a str),
+    Text(&'a str),
+}
+
+impl Chunk<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            Chunk::Number(chunk) | Chunk::Text(chunk) => chunk,
+        }
+    }
+}
+
+struct Chunks<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Chunks<'a> {
+    fn new(text: &'a str) -> Self {
+        Chunks { rest: text }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Chunk<'a>;
+
+    fn next(&mut self) -> Option<Chunk<'a>> {
+        let is_number = self.rest.chars().next()?.is_ascii_digit();
+        let end = self
+            .rest
+            .find(|c: char| c.is_ascii_digit() != is_number)
+            .unwrap_or(self.rest.len());
+        let (chunk, rest) = self.rest.split_at(end);
+        self.rest = rest;
+
+        Some(match is_number {
+            true => Chunk::Number(chunk),
+            false => Chunk::Text(chunk),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(names: &[&str], cmp: fn(&str, &str) -> Ordering) -> Vec<String> {
+        let mut names = names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        names.sort_by(|a, b| cmp(a, b));
+        names
+    }
+
+    #[test]
+    fn it_should_compare_numbers_naturally() {
+        assert_eq!(
+            sorted(
+                &["file10", "file2", "file1", "file02", "file", "v1.10.0", "v1.9.2"],
+                natural_cmp
+            ),
+            vec!["file", "file1", "file02", "file2", "file10", "v1.9.2", "v1.10.0"]
+        );
+    }
+
+    #[test]
+    fn it_should_build_locale_collation_keys() {
+        assert_eq!(locale_key("a").cmp(&locale_key("b")), Ordering::Less);
+        assert_eq!(
+            locale_key("Zebra").cmp(&locale_key("apple")),
+            Ordering::Less
+        );
+        assert_eq!(locale_key("same"), locale_key("same"));
+        assert_eq!(locale_key("nul\0byte"), b"nul\0byte".to_vec());
+    }
+}
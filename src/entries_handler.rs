@@ -1,192 +1,678 @@
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
-use std::path::Path;
+use std::fs::DirEntry;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 use crate::args::Args;
 use crate::entry::{Entry, EntryKind};
+use crate::error::{Error, ErrorSink};
+use crate::git_ignore::GitIgnore;
+use crate::pattern::Pattern;
+use crate::sort::SortOptions;
+use crate::walk::Walk;
 use clap::builder::PossibleValue;
 use clap::ValueEnum;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+const BLOCK_SIZE: u64 = 512;
+
+pub(crate) type DirId = (u64, u64);
+
+pub(crate) struct ChildDir {
+    pub(crate) relative_dir: PathBuf,
+    pub(crate) git_ignore: Option<GitIgnore>,
+    pub(crate) id: DirId,
+}
 
 pub struct EntriesHandler {
     filter_options: FilterOptions,
     sort_options: SortOptions,
+    size_options: SizeOptions,
+    max_depth: Option<u64>,
+    follow_links: bool,
+    failed: AtomicUsize,
+    exit_code: AtomicI32,
+    error_sink: Option<ErrorSink>,
+    total_sizes: Mutex<HashMap<PathBuf, u64>>,
+    thread_pool: ThreadPool,
 }
 
 struct FilterOptions {
     show_hidden: bool,
     show_only_dirs: bool,
     show_only_files: bool,
+    kinds: Vec<EntryKind>,
+    git_ignore: bool,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    newer: Option<SystemTime>,
+    older: Option<SystemTime>,
+    time_field: TimeField,
+    owned_by: Vec<u32>,
+    recursive: bool,
 }
 
-impl From<&Args> for FilterOptions {
-    fn from(item: &Args) -> Self {
-        FilterOptions {
+impl FilterOptions {
+    fn has_kind_filters(&self) -> bool {
+        self.show_only_dirs || self.show_only_files || !self.kinds.is_empty()
+    }
+
+    fn has_match_filters(&self) -> bool {
+        (self.recursive && self.has_kind_filters())
+            || !self.include.is_empty()
+            || self.min_size.is_some()
+            || self.max_size.is_some()
+            || self.newer.is_some()
+            || self.older.is_some()
+            || !self.owned_by.is_empty()
+    }
+}
+
+impl TryFrom<&Args> for FilterOptions {
+    type Error = Error;
+
+    fn try_from(item: &Args) -> Result<Self, Error> {
+        let to_patterns = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| Pattern::new(pattern, item.regex))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(FilterOptions {
             show_hidden: item.show_hidden,
             show_only_dirs: item.show_only_dirs,
             show_only_files: item.show_only_files,
-        }
+            kinds: item.kinds.clone(),
+            git_ignore: item.git_ignore,
+            include: to_patterns(&item.include)?,
+            exclude: to_patterns(&item.exclude)?,
+            min_size: item.min_size,
+            max_size: item.max_size,
+            newer: item.newer,
+            older: item.older,
+            time_field: item.time_field.clone(),
+            owned_by: item.owned_by.clone(),
+            recursive: item.recursive,
+        })
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum SortKey {
-    Name,
+pub enum TimeField {
     Created,
+    Changed,
     Modified,
-    Size,
+    Accessed,
 }
 
 //$[begin_cov_exclude]
-impl ValueEnum for SortKey {
+impl ValueEnum for TimeField {
     fn value_variants<'a>() -> &'a [Self] {
         &[
-            SortKey::Name,
-            SortKey::Created,
-            SortKey::Modified,
-            SortKey::Size,
+            TimeField::Created,
+            TimeField::Changed,
+            TimeField::Modified,
+            TimeField::Accessed,
         ]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
         match self {
-            SortKey::Name => Some(PossibleValue::new("n")),
-            SortKey::Created => Some(PossibleValue::new("c")),
-            SortKey::Modified => Some(PossibleValue::new("m")),
-            SortKey::Size => Some(PossibleValue::new("s")),
+            TimeField::Created => Some(PossibleValue::new("b").aliases(["btime", "created"])),
+            TimeField::Changed => Some(PossibleValue::new("c").aliases(["ctime", "changed"])),
+            TimeField::Modified => Some(PossibleValue::new("m").aliases(["mtime", "modified"])),
+            TimeField::Accessed => Some(PossibleValue::new("a").aliases(["atime", "accessed"])),
         }
     }
 }
 //$[end_cov_exclude]
 
-impl Display for SortKey {
+impl Display for TimeField {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let s = match self {
-            SortKey::Name => "n",
-            SortKey::Created => "c",
-            SortKey::Modified => "m",
-            SortKey::Size => "s",
+            TimeField::Created => "b",
+            TimeField::Changed => "c",
+            TimeField::Modified => "m",
+            TimeField::Accessed => "a",
         };
         write!(f, "{s}")
     }
 }
 
-struct SortOptions {
-    reverse: bool,
-    sort_key: SortKey,
+impl TimeField {
+    fn get(&self, entry: &Entry) -> Option<SystemTime> {
+        match self {
+            TimeField::Created => entry.btime,
+            TimeField::Changed => entry.ctime,
+            TimeField::Modified => Some(entry.mtime),
+            TimeField::Accessed => entry.atime,
+        }
+    }
+}
+
+struct SizeOptions {
+    total_size: bool,
+    allocated: bool,
+    recursive: bool,
 }
 
-impl From<&Args> for SortOptions {
+impl From<&Args> for SizeOptions {
     fn from(item: &Args) -> Self {
-        SortOptions {
-            reverse: item.reverse,
-            sort_key: item.sort_by.clone(),
+        SizeOptions {
+            total_size: item.total_size,
+            allocated: item.allocated,
+            recursive: item.recursive,
         }
     }
 }
 
+impl SizeOptions {
+    fn size(&self, size: u64, blocks: u64) -> u64 {
+        if self.allocated {
+            blocks * BLOCK_SIZE
+        } else {
+            size
+        }
+    }
+}
+
+#[derive(Default)]
+struct TreeSize {
+    size: u64,
+    hard_links: HashMap<(u64, u64), u64>,
+}
+
+impl TreeSize {
+    fn merge(mut self, other: TreeSize) -> Self {
+        self.size += other.size;
+        self.hard_links.extend(other.hard_links);
+        self
+    }
+
+    fn total(&self) -> u64 {
+        self.size + self.hard_links.values().sum::<u64>()
+    }
+}
+
 impl EntriesHandler {
-    pub fn new(args: &Args) -> Self {
-        let filter_options = FilterOptions::from(args);
+    pub fn new(args: &Args) -> Result<Self, Error> {
+        let filter_options = FilterOptions::try_from(args)?;
         let sort_options = SortOptions::from(args);
+        let size_options = SizeOptions::from(args);
 
-        Self {
+        Ok(Self {
             filter_options,
             sort_options,
+            size_options,
+            max_depth: args.depth,
+            follow_links: args.follow,
+            failed: AtomicUsize::new(0),
+            exit_code: AtomicI32::new(0),
+            error_sink: args.error_sink.clone(),
+            total_sizes: Mutex::new(HashMap::new()),
+            thread_pool: ThreadPoolBuilder::new()
+                .num_threads(args.threads)
+                .build()
+                .map_err(Error::ThreadPool)?,
+        })
+    }
+
+    pub fn take_result(&self) -> Result<(), Error> {
+        let failed = self.failed.swap(0, Ordering::Relaxed);
+        let code = self.exit_code.swap(0, Ordering::Relaxed);
+
+        match failed {
+            0 => Ok(()),
+            _ => Err(Error::Incomplete { failed, code }),
+        }
+    }
+
+    pub(crate) fn report(&self, error: Error) {
+        if let Some(error_sink) = &self.error_sink {
+            error_sink(&error);
         }
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        self.exit_code
+            .fetch_max(error.exit_code(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn install<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        self.thread_pool.install(f)
+    }
+
+    fn total_size(&self, path: &Path) -> u64 {
+        let size = self.total_sizes.lock().unwrap().remove(path);
+
+        size.unwrap_or_else(|| self.tree_size(path).total())
     }
 
-    pub fn get_entries(&self, path: &Path) -> Vec<Entry> {
-        let mut entries = std::fs::read_dir(path)
-            .expect("Failed to read directory")
+    fn tree_size(&self, path: &Path) -> TreeSize {
+        std::fs::read_dir(path)
+            .into_iter()
+            .flatten()
             .filter_map(Result::ok)
-            .filter(|f| {
-                self.filter_options.show_hidden || !f.file_name().to_string_lossy().starts_with('.')
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .filter_map(|dir_entry| {
+                let metadata = dir_entry.metadata().ok()?;
+                let size = self.size_options.size(metadata.len(), metadata.blocks());
+
+                if metadata.is_dir() {
+                    let path = dir_entry.path();
+                    let mut tree_size = self.tree_size(&path);
+                    if self.size_options.recursive {
+                        self.total_sizes
+                            .lock()
+                            .unwrap()
+                            .insert(path, tree_size.total());
+                    }
+                    tree_size.size += size;
+                    Some(tree_size)
+                } else if metadata.nlink() > 1 {
+                    Some(TreeSize {
+                        hard_links: HashMap::from([((metadata.dev(), metadata.ino()), size)]),
+                        ..TreeSize::default()
+                    })
+                } else {
+                    Some(TreeSize {
+                        size,
+                        ..TreeSize::default()
+                    })
+                }
+            })
+            .reduce(TreeSize::default, TreeSize::merge)
+    }
+
+    fn git_ignore(&self, path: &Path) -> Option<GitIgnore> {
+        self.filter_options.git_ignore.then(|| GitIgnore::new(path))
+    }
+
+    fn is_listed(
+        &self,
+        dir_entry: &DirEntry,
+        relative_dir: &Path,
+        git_ignore: Option<&GitIgnore>,
+    ) -> bool {
+        let name = dir_entry.file_name().to_string_lossy().to_string();
+
+        if !self.filter_options.show_hidden && name.starts_with('.') {
+            return false;
+        }
+
+        let relative_path = relative_dir.join(&name);
+        if self
+            .filter_options
+            .exclude
+            .iter()
+            .any(|pattern| pattern.is_match(&relative_path))
+        {
+            return false;
+        }
+
+        git_ignore.is_none_or(|git_ignore| {
+            let is_dir = dir_entry
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_dir());
+            !git_ignore.is_ignored(&name, is_dir)
+        })
+    }
+
+    fn is_matched(&self, entry: &Entry, relative_path: &Path) -> bool {
+        let filter_options = &self.filter_options;
+        let time = filter_options.time_field.get(entry);
+
+        (filter_options.include.is_empty()
+            || filter_options
+                .include
+                .iter()
+                .any(|pattern| pattern.is_match(relative_path)))
+            && filter_options.min_size.is_none_or(|min| entry.size >= min)
+            && filter_options.max_size.is_none_or(|max| entry.size <= max)
+            && filter_options
+                .newer
+                .is_none_or(|newer| time.is_some_and(|time| time >= newer))
+            && filter_options
+                .older
+                .is_none_or(|older| time.is_some_and(|time| time <= older))
+            && (filter_options.owned_by.is_empty() || filter_options.owned_by.contains(&entry.uid))
+            && self.is_kind_shown(entry.kind)
+    }
+
+    fn is_kind_shown(&self, kind: EntryKind) -> bool {
+        (!self.filter_options.show_only_dirs || kind == EntryKind::Dir)
+            && (!self.filter_options.show_only_files || kind == EntryKind::File)
+            && (self.filter_options.kinds.is_empty() || self.filter_options.kinds.contains(&kind))
+    }
+
+    pub(crate) fn count_entries(
+        &self,
+        path: &Path,
+        relative_dir: &Path,
+        git_ignore: Option<&GitIgnore>,
+    ) -> Option<usize> {
+        let count = std::fs::read_dir(path)
+            .ok()?
+            .filter_map(Result::ok)
+            .filter(|dir_entry| self.is_listed(dir_entry, relative_dir, git_ignore))
+            .filter_map(|dir_entry| match self.follow_links {
+                true => std::fs::metadata(dir_entry.path())
+                    .map(|metadata| metadata.file_type())
+                    .or_else(|_| dir_entry.file_type())
+                    .ok(),
+                false => dir_entry.file_type().ok(),
+            })
+            .filter(|file_type| self.is_kind_shown(EntryKind::from(*file_type)))
+            .count();
+
+        Some(count)
+    }
+
+    pub fn get_entries(&self, path: &Path) -> Result<Vec<Entry>, Error> {
+        self.install(|| {
+            let mut entries =
+                self.read_entries(path, Path::new(""), self.git_ignore(path).as_ref())?;
+            entries.retain(|e| self.is_matched(e, Path::new(&e.name)));
+
+            Ok(entries)
+        })
+    }
+
+    pub(crate) fn read_entries(
+        &self,
+        path: &Path,
+        relative_dir: &Path,
+        git_ignore: Option<&GitIgnore>,
+    ) -> Result<Vec<Entry>, Error> {
+        let read_dir = std::fs::read_dir(path).map_err(|source| Error::ReadDir {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut entries = read_dir
+            .filter_map(|dir_entry| {
+                dir_entry
+                    .map_err(|source| Error::Entry {
+                        path: path.to_path_buf(),
+                        source,
+                    })
+                    .map_err(|error| self.report(error))
+                    .ok()
             })
-            .map(|dir_entry| Entry::from_dir_entry(&dir_entry))
+            .filter(|dir_entry| self.is_listed(dir_entry, relative_dir, git_ignore))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .filter_map(|dir_entry| self.load_entry(Entry::from_dir_entry(&dir_entry)))
             .collect::<Vec<_>>();
 
-        if self.filter_options.show_only_dirs {
-            entries = entries
-                .into_iter()
-                .filter(|e| e.kind == EntryKind::Dir)
+        entries.retain(|e| {
+            self.is_kind_shown(e.kind)
+                || (self.filter_options.recursive && e.kind == EntryKind::Dir)
+        });
+        self.prepare_entries(&mut entries);
+
+        Ok(entries)
+    }
+
+    pub fn get_operands<'p>(&self, paths: &'p [PathBuf]) -> (Vec<Entry>, Vec<&'p Path>) {
+        let is_file = |path: &&Path| match std::fs::metadata(path) {
+            Ok(metadata) => !metadata.is_dir(),
+            Err(_) => std::fs::symlink_metadata(path).is_ok(),
+        };
+        let (files, dirs): (Vec<&Path>, Vec<&Path>) =
+            paths.iter().map(PathBuf::as_path).partition(is_file);
+
+        let entries = self.install(|| {
+            let mut entries = files
+                .into_par_iter()
+                .filter_map(|path| self.load_entry(Entry::from_path(path)))
                 .collect::<Vec<_>>();
+            self.prepare_entries(&mut entries);
+            entries
+        });
+
+        (entries, dirs)
+    }
+
+    fn load_entry(&self, entry: Result<Entry, Error>) -> Option<Entry> {
+        entry
+            .and_then(|mut entry| {
+                if self.follow_links {
+                    entry.follow_link()?;
+                }
+                Ok(entry)
+            })
+            .map_err(|error| self.report(error))
+            .ok()
+    }
+
+    fn prepare_entries(&self, entries: &mut [Entry]) {
+        entries.par_iter_mut().for_each(|entry| {
+            entry.size = self.size_options.size(entry.size, entry.blocks);
+            if self.size_options.total_size && entry.kind == EntryKind::Dir {
+                entry.size += self.total_size(&entry.path);
+            }
+        });
+
+        self.sort_options.sort(entries);
+    }
+
+    pub(crate) fn dir_id(path: &Path) -> Option<DirId> {
+        std::fs::metadata(path)
+            .ok()
+            .map(|metadata| (metadata.dev(), metadata.ino()))
+    }
+
+    pub(crate) fn is_loop(&self, entry: &Entry, ancestors: &[DirId]) -> bool {
+        let is_loop = ancestors.contains(&(entry.device, entry.inode));
+        if is_loop {
+            self.report(Error::Loop {
+                path: entry.path.clone(),
+            });
         }
 
-        if self.filter_options.show_only_files {
-            entries = entries
-                .into_iter()
-                .filter(|e| e.kind == EntryKind::File)
-                .collect::<Vec<_>>();
+        is_loop
+    }
+
+    pub(crate) fn is_last_level(&self, level: u64) -> bool {
+        self.max_depth.is_some_and(|max_depth| level >= max_depth)
+    }
+
+    pub(crate) fn has_match_filters(&self) -> bool {
+        self.filter_options.has_match_filters()
+    }
+
+    fn is_shown(&self, entry: &Entry, relative_dir: &Path) -> bool {
+        self.is_matched(entry, &relative_dir.join(&entry.name))
+            || !entry.children.is_empty()
+            || entry.truncated.is_some_and(|truncated| truncated > 0)
+    }
+
+    pub(crate) fn child_dir(
+        &self,
+        entry: &Entry,
+        relative_dir: &Path,
+        git_ignore: Option<&GitIgnore>,
+    ) -> ChildDir {
+        ChildDir {
+            relative_dir: relative_dir.join(&entry.name),
+            git_ignore: git_ignore.map(|git_ignore| git_ignore.child(&entry.name)),
+            id: (entry.device, entry.inode),
         }
+    }
 
-        match self.sort_options.sort_key {
-            SortKey::Name => entries.sort_by(|a, b| a.name.cmp(&b.name)),
-            SortKey::Created => entries.sort_by(|a, b| a.ctime.cmp(&b.ctime)),
-            SortKey::Modified => entries.sort_by(|a, b| a.mtime.cmp(&b.mtime)),
-            SortKey::Size => entries.sort_by(|a, b| a.size.cmp(&b.size)),
+    pub(crate) fn expand(
+        &self,
+        entry: &mut Entry,
+        child_dir: &ChildDir,
+        ancestors: &[DirId],
+        level: u64,
+    ) -> Option<Vec<Entry>> {
+        if self.is_loop(entry, ancestors) {
+            return None;
         }
 
-        if self.sort_options.reverse {
-            entries.reverse();
+        if self.is_last_level(level) {
+            if entry.truncated.is_none() {
+                entry.truncated = self.count_entries(
+                    &entry.path,
+                    &child_dir.relative_dir,
+                    child_dir.git_ignore.as_ref(),
+                );
+            }
+            return None;
         }
 
-        entries
+        self.install(|| {
+            self.read_entries(
+                &entry.path,
+                &child_dir.relative_dir,
+                child_dir.git_ignore.as_ref(),
+            )
+        })
+        .map_err(|error| self.report(error.nested()))
+        .ok()
     }
 
-    pub fn get_entries_recursive(&self, path: &Path) -> Vec<Entry> {
-        let mut entries = self.get_entries(path);
-
-        for entry in entries.iter_mut() {
-            if entry.kind == EntryKind::Dir {
-                entry.children = self.get_entries_recursive(&path.join(&entry.name));
-            }
+    pub(crate) fn is_kept(
+        &self,
+        entry: &mut Entry,
+        relative_dir: &Path,
+        git_ignore: Option<&GitIgnore>,
+        ancestors: &[DirId],
+        level: u64,
+    ) -> bool {
+        if self.is_shown(entry, relative_dir) {
+            return true;
         }
+        if entry.kind != EntryKind::Dir {
+            return false;
+        }
+
+        let child_dir = self.child_dir(entry, relative_dir, git_ignore);
+        let Some(mut children) = self.expand(entry, &child_dir, ancestors, level) else {
+            return self.is_shown(entry, relative_dir);
+        };
+        let ancestors = [ancestors, &[child_dir.id]].concat();
+        let Some(first) = children.iter_mut().position(|child| {
+            self.is_kept(
+                child,
+                &child_dir.relative_dir,
+                child_dir.git_ignore.as_ref(),
+                &ancestors,
+                level + 1,
+            )
+        }) else {
+            return false;
+        };
+
+        children.drain(..first);
+        entry.children = children;
+        true
+    }
+
+    pub fn walk(&self, path: &Path) -> Result<Walk<'_>, Error> {
+        let git_ignore = self.git_ignore(path);
+        let entries =
+            self.install(|| self.read_entries(path, Path::new(""), git_ignore.as_ref()))?;
+
+        Ok(Walk::lazy(self, entries, git_ignore, Self::dir_id(path)))
+    }
+
+    pub fn get_entries_recursive(&self, path: &Path) -> Result<Vec<Entry>, Error> {
+        let git_ignore = self.git_ignore(path);
 
+        self.install(|| {
+            let mut entries = self.read_entries(path, Path::new(""), git_ignore.as_ref())?;
+            self.expand_all(
+                &mut entries,
+                Path::new(""),
+                git_ignore.as_ref(),
+                &Self::dir_id(path).into_iter().collect::<Vec<_>>(),
+                1,
+            );
+
+            Ok(entries)
+        })
+    }
+
+    fn expand_all(
+        &self,
+        entries: &mut Vec<Entry>,
+        relative_dir: &Path,
+        git_ignore: Option<&GitIgnore>,
+        ancestors: &[DirId],
+        level: u64,
+    ) {
         entries
+            .par_iter_mut()
+            .filter(|entry| entry.kind == EntryKind::Dir)
+            .for_each(|entry| {
+                let child_dir = self.child_dir(entry, relative_dir, git_ignore);
+                if let Some(mut children) = self.expand(entry, &child_dir, ancestors, level) {
+                    self.expand_all(
+                        &mut children,
+                        &child_dir.relative_dir,
+                        child_dir.git_ignore.as_ref(),
+                        &[ancestors, &[child_dir.id]].concat(),
+                        level + 1,
+                    );
+                    entry.children = children;
+                }
+            });
+
+        entries.retain(|entry| self.is_shown(entry, relative_dir));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sort::SortKey;
+    use crate::walk::collect_tree;
     use assert_fs::prelude::*;
+    use std::os::unix::net::UnixListener;
 
     #[test]
     fn it_should_create_filter_options() {
         let args = Args::default();
-        let filter_options = FilterOptions::from(&args);
-
-        assert_eq!(filter_options.show_hidden, false);
-        assert_eq!(filter_options.show_only_dirs, false);
-        assert_eq!(filter_options.show_only_files, false);
-    }
-
-    #[test]
-    fn it_should_create_sort_options() {
-        let args = Args::default();
-        let sort_options = SortOptions::from(&args);
+        let filter_options = FilterOptions::try_from(&args).unwrap();
 
-        assert_eq!(sort_options.reverse, false);
-        assert_eq!(sort_options.sort_key, SortKey::Name);
+        assert!(!filter_options.show_hidden);
+        assert!(!filter_options.show_only_dirs);
+        assert!(!filter_options.show_only_files);
+        assert!(filter_options.kinds.is_empty());
+        assert!(!filter_options.git_ignore);
     }
 
     #[test]
     fn it_should_create_entries_handler() {
         let args = Args::default();
-        let entries_handler = EntriesHandler::new(&args);
+        let entries_handler = EntriesHandler::new(&args).unwrap();
 
-        assert_eq!(entries_handler.filter_options.show_hidden, false);
-        assert_eq!(entries_handler.filter_options.show_only_dirs, false);
-        assert_eq!(entries_handler.filter_options.show_only_files, false);
-        assert_eq!(entries_handler.sort_options.reverse, false);
-        assert_eq!(entries_handler.sort_options.sort_key, SortKey::Name);
+        assert!(!entries_handler.filter_options.show_hidden);
+        assert!(!entries_handler.filter_options.show_only_dirs);
+        assert!(!entries_handler.filter_options.show_only_files);
+        assert!(!entries_handler.sort_options.reverse);
+        assert_eq!(
+            entries_handler.sort_options.sort_by,
+            vec![SortKey::Name.into()]
+        );
     }
 
     #[test]
-    fn it_should_parse_sort_keys() {
-        assert_eq!("n", SortKey::Name.to_string());
-        assert_eq!("c", SortKey::Created.to_string());
-        assert_eq!("m", SortKey::Modified.to_string());
-        assert_eq!("s", SortKey::Size.to_string());
+    fn it_should_parse_time_fields() {
+        assert_eq!("b", TimeField::Created.to_string());
+        assert_eq!("c", TimeField::Changed.to_string());
+        assert_eq!("m", TimeField::Modified.to_string());
+        assert_eq!("a", TimeField::Accessed.to_string());
     }
 
     #[test]
@@ -197,12 +683,24 @@ mod tests {
         temp.child("file3").touch().unwrap();
 
         let args = Args::default();
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries.len(), 3);
     }
 
+    #[test]
+    fn it_should_fail_when_directory_cannot_be_read() {
+        let temp = assert_fs::TempDir::new().unwrap();
+
+        let args = Args::default();
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let result = entries_handler.get_entries(&temp.path().join("missing"));
+
+        assert!(matches!(result, Err(Error::ReadDir { .. })));
+        assert!(entries_handler.take_result().is_ok());
+    }
+
     #[test]
     fn it_should_get_entries_recursively() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -216,13 +714,479 @@ mod tests {
         dir1.child("file3").touch().unwrap();
 
         let args = Args::default();
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries_recursive(temp.path());
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
 
         assert_eq!(entries.len(), 4);
         assert_eq!(entries[0].children.len(), 3);
     }
 
+    #[test]
+    fn it_should_follow_symlinked_directories() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("target/file").touch().unwrap();
+        let outside = temp.child("dir");
+        outside.create_dir_all().unwrap();
+        outside
+            .child("link")
+            .symlink_to_dir(temp.child("target").path())
+            .unwrap();
+
+        let entries_handler = EntriesHandler::new(&Args::default()).unwrap();
+        let entries = entries_handler
+            .get_entries_recursive(outside.path())
+            .unwrap();
+
+        assert_eq!(entries[0].kind, EntryKind::Symlink);
+        assert!(entries[0].children.is_empty());
+
+        let args = Args {
+            follow: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler
+            .get_entries_recursive(outside.path())
+            .unwrap();
+
+        assert_eq!(entries[0].kind, EntryKind::Dir);
+        assert_eq!(entries[0].children[0].name, "file");
+    }
+
+    #[test]
+    fn it_should_detect_symlink_loops() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let dir = temp.child("dir");
+        dir.child("nested").create_dir_all().unwrap();
+        dir.child("nested/loop").symlink_to_dir(dir.path()).unwrap();
+
+        let args = Args {
+            follow: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+        let result = entries_handler.take_result();
+
+        assert_eq!(
+            names(&entries),
+            ["dir", "dir/nested", "dir/nested/loop"].map(|name| temp
+                .path()
+                .join(name)
+                .to_string_lossy()
+                .to_string())
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Incomplete { failed: 1, code: 1 })
+        ));
+
+        let entries = collect_tree(&mut entries_handler.walk(temp.path()).unwrap());
+        let result = entries_handler.take_result();
+
+        assert_eq!(
+            names(&entries),
+            ["dir", "dir/nested", "dir/nested/loop"].map(|name| temp
+                .path()
+                .join(name)
+                .to_string_lossy()
+                .to_string())
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Incomplete { failed: 1, code: 1 })
+        ));
+    }
+
+    #[test]
+    fn it_should_split_operands_into_files_and_dirs() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("small").write_str("1").unwrap();
+        temp.child("large").write_str("1234567890").unwrap();
+
+        let args = Args {
+            sort_by: vec![SortKey::Size.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let paths = ["large", "dir", "small", "missing"].map(|name| temp.path().join(name));
+        let (files, dirs) = entries_handler.get_operands(&paths);
+
+        assert_eq!(
+            names(&files),
+            [&paths[2], &paths[0]].map(|path| path.to_string_lossy().to_string())
+        );
+        assert_eq!(dirs, vec![paths[1].as_path(), paths[3].as_path()]);
+    }
+
+    #[test]
+    fn it_should_stop_at_max_depth() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let dir1 = temp.child("dir1");
+        dir1.child("dir2").create_dir_all().unwrap();
+        dir1.child("file1").touch().unwrap();
+        dir1.child(".hidden").touch().unwrap();
+        dir1.child("dir2").child("file2").touch().unwrap();
+
+        let args = Args {
+            depth: Some(1),
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].children.is_empty());
+        assert_eq!(entries[0].truncated, Some(2));
+
+        let args = Args {
+            depth: Some(2),
+            show_only_files: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(dir1.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].truncated, None);
+
+        let args = Args {
+            depth: Some(2),
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries[0].truncated, None);
+        assert_eq!(entries[0].children[0].name, "dir2");
+        assert_eq!(entries[0].children[0].truncated, Some(1));
+    }
+
+    #[test]
+    fn it_should_respect_git_ignore() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child(".git").create_dir_all().unwrap();
+        temp.child(".gitignore").write_str("target/\n").unwrap();
+        temp.child("target/debug").create_dir_all().unwrap();
+        temp.child("src/.gitignore").write_str("*.rs.bk\n").unwrap();
+        temp.child("src/main.rs").touch().unwrap();
+        temp.child("src/main.rs.bk").touch().unwrap();
+
+        let args = Args {
+            git_ignore: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "src");
+        assert_eq!(entries[0].children.len(), 1);
+        assert_eq!(entries[0].children[0].name, "main.rs");
+
+        let entries = EntriesHandler::new(&Args::default())
+            .unwrap()
+            .get_entries(temp.path())
+            .unwrap();
+
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn it_should_prune_excluded_subtrees() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("node_modules/pkg/index.js").touch().unwrap();
+        temp.child("src/main.rs").touch().unwrap();
+        temp.child("src/generated/out.rs").touch().unwrap();
+
+        let args = Args {
+            exclude: vec![String::from("node_modules"), String::from("src/gen*")],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "src");
+        assert_eq!(entries[0].children.len(), 1);
+        assert_eq!(entries[0].children[0].name, "main.rs");
+    }
+
+    #[test]
+    fn it_should_keep_directories_leading_to_included_entries() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("README.md").touch().unwrap();
+        temp.child("docs/guide.md").touch().unwrap();
+        temp.child("src/main.rs").touch().unwrap();
+        temp.child("src/render/text.rs").touch().unwrap();
+
+        let args = Args {
+            include: vec![String::from("**/*.rs")],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "src");
+        assert_eq!(entries[0].children[0].name, "main.rs");
+        assert_eq!(entries[0].children[1].name, "render");
+        assert_eq!(entries[0].children[1].children[0].name, "text.rs");
+
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn it_should_filter_with_regexes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("test_cli.py").touch().unwrap();
+        temp.child("cli.py").touch().unwrap();
+        temp.child("cli_test.py").touch().unwrap();
+
+        let args = Args {
+            include: vec![String::from(r"\.py$")],
+            exclude: vec![String::from("^test_")],
+            regex: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "cli.py");
+        assert_eq!(entries[1].name, "cli_test.py");
+    }
+
+    #[test]
+    fn it_should_fail_on_invalid_patterns() {
+        let args = Args {
+            include: vec![String::from("(")],
+            regex: true,
+            ..Args::default()
+        };
+
+        assert!(matches!(
+            EntriesHandler::new(&args),
+            Err(Error::Pattern { .. })
+        ));
+    }
+
+    #[test]
+    fn it_should_filter_by_size_range() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("empty").touch().unwrap();
+        temp.child("small").write_str("1234567890").unwrap();
+        temp.child("large").write_str(&"x".repeat(2_000)).unwrap();
+        temp.child("dir/huge")
+            .write_str(&"x".repeat(5_000))
+            .unwrap();
+        temp.child("other").create_dir_all().unwrap();
+
+        let args = Args {
+            min_size: Some(5),
+            max_size: Some(1_000),
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "small");
+
+        let args = Args {
+            min_size: Some(4_097),
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "dir");
+        assert_eq!(entries[0].children[0].name, "huge");
+    }
+
+    #[test]
+    fn it_should_filter_by_owner() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir/file").touch().unwrap();
+        let uid = std::fs::metadata(temp.path()).unwrap().uid();
+
+        let args = Args {
+            owned_by: vec![uid],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert_eq!(entries[0].children[0].name, "file");
+
+        let args = Args {
+            owned_by: vec![uid.wrapping_add(1)],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries_recursive(temp.path()).unwrap();
+
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn it_should_filter_by_time_range() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("old").touch().unwrap();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        let middle = SystemTime::now();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        temp.child("new").touch().unwrap();
+
+        let args = Args {
+            newer: Some(middle),
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "new");
+
+        let args = Args {
+            older: Some(middle),
+            time_field: TimeField::Created,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "old");
+    }
+
+    #[test]
+    fn it_should_compute_total_sizes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").write_str("1234567890").unwrap();
+        temp.child("dir/file").write_str(&"x".repeat(100)).unwrap();
+        temp.child("dir/.hidden")
+            .write_str(&"x".repeat(1_000))
+            .unwrap();
+        temp.child("dir/nested/file")
+            .write_str(&"x".repeat(10_000))
+            .unwrap();
+
+        let dir_size = |path: &str| std::fs::metadata(temp.path().join(path)).unwrap().len();
+        let args = Args {
+            total_size: true,
+            sort_by: vec![SortKey::Size.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries[0].name, "file");
+        assert_eq!(entries[0].size, 10);
+        assert_eq!(entries[1].name, "dir");
+        assert_eq!(
+            entries[1].size,
+            dir_size("dir") + 100 + 1_000 + dir_size("dir/nested") + 10_000
+        );
+        assert!(entries_handler.total_sizes.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_should_count_hard_linked_files_once_in_total_sizes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir/file").write_str(&"x".repeat(100)).unwrap();
+        temp.child("dir/nested").create_dir_all().unwrap();
+        std::fs::hard_link(temp.path().join("dir/file"), temp.path().join("dir/link")).unwrap();
+        std::fs::hard_link(
+            temp.path().join("dir/file"),
+            temp.path().join("dir/nested/link"),
+        )
+        .unwrap();
+
+        let dir_size = |path: &str| std::fs::metadata(temp.path().join(path)).unwrap().len();
+        let args = Args {
+            total_size: true,
+            recursive: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+        assert!(!entries_handler.total_sizes.lock().unwrap().is_empty());
+        let nested = entries_handler
+            .get_entries(&temp.path().join("dir"))
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.name == "nested")
+            .unwrap();
+
+        assert_eq!(
+            entries[0].size,
+            dir_size("dir") + dir_size("dir/nested") + 100
+        );
+        assert_eq!(nested.size, dir_size("dir/nested") + 100);
+        assert!(entries_handler.total_sizes.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_should_use_allocated_sizes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").write_str("1234567890").unwrap();
+
+        let args = Args {
+            allocated: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries[0].size, entries[0].blocks * BLOCK_SIZE);
+        assert_eq!(entries[0].size % BLOCK_SIZE, 0);
+    }
+
+    fn names(entries: &[Entry]) -> Vec<String> {
+        entries
+            .iter()
+            .flat_map(|entry| {
+                std::iter::once(entry.path.to_string_lossy().to_string())
+                    .chain(names(&entry.children))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_should_walk_the_same_tree_with_any_number_of_threads() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        for dir in 0..5 {
+            for nested in 0..5 {
+                for file in 0..5 {
+                    temp.child(format!("dir{dir}/nested{nested}/file{file}"))
+                        .write_str(&"x".repeat(file * 10))
+                        .unwrap();
+                }
+            }
+        }
+
+        let walk = |threads: usize, sort_by: SortKey| {
+            let args = Args {
+                threads,
+                sort_by: vec![sort_by.into()],
+                ..Args::default()
+            };
+            let entries_handler = EntriesHandler::new(&args).unwrap();
+            names(&entries_handler.get_entries_recursive(temp.path()).unwrap())
+        };
+
+        let sequential = walk(1, SortKey::Name);
+        assert_eq!(sequential.len(), 5 + 5 * 5 + 5 * 5 * 5);
+        assert_eq!(walk(4, SortKey::Name), sequential);
+        assert_eq!(walk(0, SortKey::Name), sequential);
+        assert_eq!(walk(4, SortKey::Size), walk(1, SortKey::Size));
+    }
+
     #[test]
     fn it_should_sort_by_name() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -230,10 +1194,12 @@ mod tests {
         temp.child("file2").touch().unwrap();
         temp.child("file3").touch().unwrap();
 
-        let mut args = Args::default();
-        args.sort_by = SortKey::Name;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            sort_by: vec![SortKey::Name.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries[0].name, "file1");
         assert_eq!(entries[1].name, "file2");
@@ -249,10 +1215,12 @@ mod tests {
         std::thread::sleep(std::time::Duration::from_secs(1));
         temp.child("file3").touch().unwrap();
 
-        let mut args = Args::default();
-        args.sort_by = SortKey::Created;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            sort_by: vec![SortKey::Created.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries[0].name, "file1");
         assert_eq!(entries[1].name, "file2");
@@ -268,16 +1236,73 @@ mod tests {
         std::thread::sleep(std::time::Duration::from_secs(1));
         temp.child("file3").touch().unwrap();
 
-        let mut args = Args::default();
-        args.sort_by = SortKey::Modified;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            sort_by: vec![SortKey::Modified.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries[0].name, "file1");
         assert_eq!(entries[1].name, "file2");
         assert_eq!(entries[2].name, "file3");
     }
 
+    #[test]
+    fn it_should_sort_names_naturally_and_case_insensitively() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        for name in ["file10", "file2", "Zebra", "apple"] {
+            temp.child(name).touch().unwrap();
+        }
+        let sorted_names = |sort_by: SortKey| {
+            let args = Args {
+                sort_by: vec![sort_by.into()],
+                ..Args::default()
+            };
+            let entries_handler = EntriesHandler::new(&args).unwrap();
+            entries_handler
+                .get_entries(temp.path())
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted_names(SortKey::Name),
+            vec!["Zebra", "apple", "file10", "file2"]
+        );
+        assert_eq!(
+            sorted_names(SortKey::Natural),
+            vec!["Zebra", "apple", "file2", "file10"]
+        );
+        assert_eq!(
+            sorted_names(SortKey::CaseInsensitive),
+            vec!["apple", "file10", "file2", "Zebra"]
+        );
+        assert_eq!(
+            sorted_names(SortKey::Locale),
+            vec!["Zebra", "apple", "file10", "file2"]
+        );
+    }
+
+    #[test]
+    fn it_should_sort_by_owner_then_name() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file2").touch().unwrap();
+        temp.child("file1").touch().unwrap();
+
+        let args = Args {
+            sort_by: vec![SortKey::Owner.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries[0].name, "file1");
+        assert_eq!(entries[1].name, "file2");
+    }
+
     #[test]
     fn it_should_sort_by_size() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -287,10 +1312,12 @@ mod tests {
             .write_str("12345678901234567890")
             .unwrap();
 
-        let mut args = Args::default();
-        args.sort_by = SortKey::Size;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            sort_by: vec![SortKey::Size.into()],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries[0].name, "file1");
         assert_eq!(entries[1].name, "file2");
@@ -309,10 +1336,12 @@ mod tests {
         dir1.child("file2").touch().unwrap();
         dir1.child("file3").touch().unwrap();
 
-        let mut args = Args::default();
-        args.show_only_dirs = true;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            show_only_dirs: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].name, "dir1");
@@ -330,10 +1359,12 @@ mod tests {
         dir1.child("file2").touch().unwrap();
         dir1.child("file3").touch().unwrap();
 
-        let mut args = Args::default();
-        args.show_only_files = true;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            show_only_files: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries.len(), 3);
         assert_eq!(entries[0].name, "file1");
@@ -341,6 +1372,26 @@ mod tests {
         assert_eq!(entries[2].name, "file3");
     }
 
+    #[test]
+    fn it_should_show_only_given_kinds() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file1").touch().unwrap();
+        temp.child("dir1").create_dir_all().unwrap();
+        let _listener = UnixListener::bind(temp.child("socket1").path()).unwrap();
+
+        let args = Args {
+            kinds: vec![EntryKind::Socket, EntryKind::Dir],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "dir1");
+        assert_eq!(entries[1].name, "socket1");
+        assert_eq!(entries[1].kind, EntryKind::Socket);
+    }
+
     #[test]
     fn it_should_reverse_sort_order() {
         let temp = assert_fs::TempDir::new().unwrap();
@@ -348,10 +1399,12 @@ mod tests {
         temp.child("file2").touch().unwrap();
         temp.child("file3").touch().unwrap();
 
-        let mut args = Args::default();
-        args.reverse = true;
-        let entries_handler = EntriesHandler::new(&args);
-        let entries = entries_handler.get_entries(temp.path());
+        let args = Args {
+            reverse: true,
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+        let entries = entries_handler.get_entries(temp.path()).unwrap();
 
         assert_eq!(entries[0].name, "file3");
         assert_eq!(entries[1].name, "file2");
@@ -1,114 +1,154 @@
 use std::{
-    fs::{DirEntry, FileType},
-    os::unix::fs::PermissionsExt,
-    time::SystemTime,
+    fmt::{self, Display, Formatter},
+    fs::{self, DirEntry, FileType, Metadata},
+    io,
+    os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use file_mode::Mode;
-use humansize::DECIMAL;
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
 
-use crate::args::Args;
+use crate::error::Error;
 
 pub struct Entry {
     pub kind: EntryKind,
     pub name: String,
+    pub path: PathBuf,
     pub size: u64,
+    pub blocks: u64,
     pub mtime: SystemTime,
-    pub ctime: SystemTime,
+    pub btime: Option<SystemTime>,
+    pub ctime: Option<SystemTime>,
+    pub atime: Option<SystemTime>,
     pub permissions: u32,
+    pub device: u64,
+    pub inode: u64,
+    pub nlink: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub link_target: Option<PathBuf>,
+    pub is_broken_link: bool,
     pub children: Vec<Entry>,
+    pub truncated: Option<usize>,
 }
 
-pub struct DisplayOptions {
-    show_emoji_icon: bool,
-    show_modified_ts: bool,
-    show_created_ts: bool,
-    show_size: bool,
-    show_permissions: bool,
-}
-
-impl From<&Args> for DisplayOptions {
-    fn from(item: &Args) -> Self {
-        DisplayOptions {
-            show_emoji_icon: item.show_emoji_icon,
-            show_modified_ts: item.show_modified_ts,
-            show_created_ts: item.show_created_ts,
-            show_size: item.show_size,
-            show_permissions: item.show_permissions,
+fn is_broken_link(path: &Path) -> io::Result<bool> {
+    match fs::metadata(path) {
+        Ok(_) => Ok(false),
+        Err(error)
+            if matches!(
+                error.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::NotADirectory
+            ) =>
+        {
+            Ok(true)
         }
+        Err(error) => Err(error),
     }
 }
 
+fn change_time(metadata: &Metadata) -> Option<SystemTime> {
+    let seconds = u64::try_from(metadata.ctime()).ok()?;
+    let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+
+    UNIX_EPOCH.checked_add(Duration::new(seconds, nanos))
+}
+
 impl Entry {
-    pub fn from_dir_entry(dir_entry: &DirEntry) -> Self {
-        let metadata = dir_entry.metadata().unwrap();
-        Entry {
-            kind: dir_entry.file_type().unwrap().into(),
-            name: dir_entry.file_name().to_string_lossy().to_string(),
-            size: metadata.len(),
-            mtime: metadata.modified().unwrap(),
-            ctime: metadata.created().unwrap(),
-            permissions: metadata.permissions().mode(),
-            children: vec![],
-        }
+    pub fn from_dir_entry(dir_entry: &DirEntry) -> Result<Self, Error> {
+        let to_error = |source| Error::Entry {
+            path: dir_entry.path(),
+            source,
+        };
+        let metadata = dir_entry.metadata().map_err(to_error)?;
+
+        Self::from_metadata(
+            dir_entry.file_name().to_string_lossy().to_string(),
+            dir_entry.path(),
+            &metadata,
+        )
+        .map_err(to_error)
     }
 
-    fn format_st_mode(st_mode: u32) -> String {
-        let perms = Mode::from(st_mode)
-            .to_string()
-            .chars()
-            .skip(1)
-            .collect::<String>();
-        let mut result = String::with_capacity(11);
-
-        for (i, c) in perms.chars().enumerate() {
-            if i > 0 && i % 3 == 0 {
-                result.push('|');
-            }
-            result.push(c);
-        }
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let to_error = |source| Error::Entry {
+            path: path.to_path_buf(),
+            source,
+        };
+        let metadata = fs::symlink_metadata(path).map_err(to_error)?;
 
-        format!("[{}]", result)
+        Self::from_metadata(
+            path.to_string_lossy().to_string(),
+            path.to_path_buf(),
+            &metadata,
+        )
+        .map_err(to_error)
     }
 
-    pub fn display(&self, display_options: &DisplayOptions) -> String {
-        let mut metadata = vec![];
-
-        if display_options.show_permissions {
-            metadata.push(format!("─{}", Self::format_st_mode(self.permissions)));
-        }
+    fn from_metadata(name: String, path: PathBuf, metadata: &Metadata) -> io::Result<Self> {
+        let kind = EntryKind::from(metadata.file_type());
+        let (link_target, is_broken_link) = match kind {
+            EntryKind::Symlink => (fs::read_link(&path).ok(), is_broken_link(&path)?),
+            _ => (None, false),
+        };
 
-        if display_options.show_modified_ts {
-            metadata.push(format!(
-                "─[M: {: <14}]",
-                timeago::Formatter::new().convert(self.mtime.elapsed().unwrap())
-            ));
-        }
+        Ok(Entry {
+            kind,
+            name,
+            path,
+            size: metadata.len(),
+            blocks: metadata.blocks(),
+            mtime: metadata.modified()?,
+            btime: metadata.created().ok(),
+            ctime: change_time(metadata),
+            atime: metadata.accessed().ok(),
+            permissions: metadata.permissions().mode(),
+            device: metadata.dev(),
+            inode: metadata.ino(),
+            nlink: metadata.nlink(),
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            link_target,
+            is_broken_link,
+            children: vec![],
+            truncated: None,
+        })
+    }
 
-        if display_options.show_created_ts {
-            metadata.push(format!(
-                "─[C: {: <14}]",
-                timeago::Formatter::new().convert(self.ctime.elapsed().unwrap())
-            ));
-        }
+    fn apply_metadata(&mut self, metadata: &Metadata) -> io::Result<()> {
+        self.mtime = metadata.modified()?;
+        self.kind = metadata.file_type().into();
+        self.size = metadata.len();
+        self.blocks = metadata.blocks();
+        self.btime = metadata.created().ok();
+        self.ctime = change_time(metadata);
+        self.atime = metadata.accessed().ok();
+        self.permissions = metadata.permissions().mode();
+        self.device = metadata.dev();
+        self.inode = metadata.ino();
+        self.nlink = metadata.nlink();
+        self.uid = metadata.uid();
+        self.gid = metadata.gid();
+
+        Ok(())
+    }
 
-        if display_options.show_size {
-            metadata.push(format!(
-                "─[S: {: <10}]",
-                humansize::format_size(self.size, DECIMAL)
-            ));
+    pub fn follow_link(&mut self) -> Result<(), Error> {
+        if self.kind != EntryKind::Symlink || self.is_broken_link {
+            return Ok(());
         }
 
-        let metadata = metadata.iter().fold(String::new(), |acc, e| acc + e);
-        format!(
-            "[{}]{}─[{}]",
-            self.get_icon(display_options.show_emoji_icon),
-            metadata,
-            self.name
-        )
+        fs::metadata(&self.path)
+            .and_then(|metadata| self.apply_metadata(&metadata))
+            .map_err(|source| Error::Entry {
+                path: self.path.clone(),
+                source,
+            })
     }
 
-    fn get_icon(&self, use_emoji_icon: bool) -> &str {
+    pub fn get_icon(&self, use_emoji_icon: bool) -> &str {
         match self.kind {
             EntryKind::Dir => {
                 if use_emoji_icon {
@@ -131,28 +171,55 @@ impl Entry {
                     "L"
                 }
             }
-        }
-    }
-
-    pub fn display_recursive(&self, display_options: &DisplayOptions, depth: usize) {
-        println!(
-            "{:<depth$}{}{}",
-            "",
-            if depth > 0 { "└" } else { "" },
-            self.display(display_options),
-        );
-
-        for child in self.children.iter() {
-            child.display_recursive(display_options, depth + 1);
+            EntryKind::Fifo => {
+                if use_emoji_icon {
+                    "🚰"
+                } else {
+                    "P"
+                }
+            }
+            EntryKind::Socket => {
+                if use_emoji_icon {
+                    "🔌"
+                } else {
+                    "S"
+                }
+            }
+            EntryKind::BlockDevice => {
+                if use_emoji_icon {
+                    "💽"
+                } else {
+                    "B"
+                }
+            }
+            EntryKind::CharDevice => {
+                if use_emoji_icon {
+                    "📟"
+                } else {
+                    "C"
+                }
+            }
+            EntryKind::Unknown => {
+                if use_emoji_icon {
+                    "❓"
+                } else {
+                    "?"
+                }
+            }
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum EntryKind {
     Dir,
     File,
     Symlink,
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+    Unknown,
 }
 
 impl From<FileType> for EntryKind {
@@ -163,18 +230,73 @@ impl From<FileType> for EntryKind {
             EntryKind::File
         } else if value.is_symlink() {
             EntryKind::Symlink
+        } else if value.is_fifo() {
+            EntryKind::Fifo
+        } else if value.is_socket() {
+            EntryKind::Socket
+        } else if value.is_block_device() {
+            EntryKind::BlockDevice
+        } else if value.is_char_device() {
+            EntryKind::CharDevice
         } else {
-            panic!("Unknown file type")
+            EntryKind::Unknown
         }
     }
 }
 
+//$[begin_cov_exclude]
+impl ValueEnum for EntryKind {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            EntryKind::Dir,
+            EntryKind::File,
+            EntryKind::Symlink,
+            EntryKind::Fifo,
+            EntryKind::Socket,
+            EntryKind::BlockDevice,
+            EntryKind::CharDevice,
+            EntryKind::Unknown,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            EntryKind::Dir => Some(PossibleValue::new("d")),
+            EntryKind::File => Some(PossibleValue::new("f")),
+            EntryKind::Symlink => Some(PossibleValue::new("l")),
+            EntryKind::Fifo => Some(PossibleValue::new("p")),
+            EntryKind::Socket => Some(PossibleValue::new("s")),
+            EntryKind::BlockDevice => Some(PossibleValue::new("b")),
+            EntryKind::CharDevice => Some(PossibleValue::new("c")),
+            EntryKind::Unknown => None,
+        }
+    }
+}
+//$[end_cov_exclude]
+
+impl Display for EntryKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            EntryKind::Dir => "d",
+            EntryKind::File => "f",
+            EntryKind::Symlink => "l",
+            EntryKind::Fifo => "p",
+            EntryKind::Socket => "s",
+            EntryKind::BlockDevice => "b",
+            EntryKind::CharDevice => "c",
+            EntryKind::Unknown => "?",
+        };
+        write!(f, "{s}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use assert_fs::prelude::*;
-    use regex::Regex;
     use std::fs;
+    use std::os::unix::net::UnixListener;
+    use std::process::Command;
 
     #[test]
     fn it_should_create_entry_from_dir_entry() {
@@ -186,180 +308,143 @@ mod tests {
 
         let entries = fs::read_dir(temp.path())
             .unwrap()
-            .into_iter()
-            .map(|e| Entry::from_dir_entry(&e.unwrap()))
+            .map(|e| Entry::from_dir_entry(&e.unwrap()).unwrap())
             .collect::<Vec<Entry>>();
 
         assert_eq!(entries.len(), 3);
-        assert_eq!(entries.iter().any(|e| e.kind == EntryKind::Dir), true);
-        assert_eq!(entries.iter().any(|e| e.kind == EntryKind::File), true);
-        assert_eq!(entries.iter().any(|e| e.kind == EntryKind::Symlink), true);
-        assert_eq!(entries.iter().any(|e| e.name == "child_dir"), true);
-        assert_eq!(entries.iter().any(|e| e.name == "file"), true);
-        assert_eq!(entries.iter().any(|e| e.name == "symlink"), true);
-    }
-
-    #[test]
-    fn it_should_create_display_options() {
-        let args = Args::default();
-        let display_options = DisplayOptions::from(&args);
-        assert_eq!(display_options.show_emoji_icon, false);
-        assert_eq!(display_options.show_modified_ts, false);
-        assert_eq!(display_options.show_created_ts, false);
-        assert_eq!(display_options.show_size, false);
-        assert_eq!(display_options.show_permissions, false);
+        assert!(entries.iter().any(|e| e.kind == EntryKind::Dir));
+        assert!(entries.iter().any(|e| e.kind == EntryKind::File));
+        assert!(entries.iter().any(|e| e.kind == EntryKind::Symlink));
+        assert!(entries.iter().any(|e| e.name == "child_dir"));
+        assert!(entries.iter().any(|e| e.name == "file"));
+        assert!(entries.iter().any(|e| e.name == "symlink"));
     }
 
     #[test]
-    fn it_should_format_st_mode() {
-        assert_eq!(Entry::format_st_mode(0o644), "[rw-|r--|r--]");
-        assert_eq!(Entry::format_st_mode(0o755), "[rwx|r-x|r-x]");
-        assert_eq!(Entry::format_st_mode(0o777), "[rwx|rwx|rwx]");
-    }
-
-    #[test]
-    fn it_should_display_permissions() {
-        let display_options = DisplayOptions {
-            show_emoji_icon: false,
-            show_modified_ts: false,
-            show_created_ts: false,
-            show_size: false,
-            show_permissions: true,
-        };
-
+    fn it_should_create_entry_from_path() {
         let temp = assert_fs::TempDir::new().unwrap();
-        temp.child("file").touch().unwrap();
-
-        let mut read_dir = fs::read_dir(temp.path()).unwrap().into_iter();
-        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap());
+        let file = temp.child("file");
+        file.write_str("1234").unwrap();
 
-        let re =
-            Regex::new(r"^\[F\]─\[(r|-)(w|-)(x|-)\|(r|-)(w|-)(x|-)\|(r|-)(w|-)(x|-)\]─\[file\]$")
-                .unwrap();
+        let entry = Entry::from_path(file.path()).unwrap();
 
-        assert!(re.is_match(file_entry.display(&display_options).as_str()));
+        assert_eq!(entry.kind, EntryKind::File);
+        assert_eq!(entry.name, file.path().to_string_lossy());
+        assert_eq!(entry.size, 4);
+        assert!(Entry::from_path(&temp.path().join("missing")).is_err());
     }
 
     #[test]
-    fn it_should_display_modified_ts() {
-        let display_options = DisplayOptions {
-            show_emoji_icon: false,
-            show_modified_ts: true,
-            show_created_ts: false,
-            show_size: false,
-            show_permissions: false,
-        };
-
+    fn it_should_read_link_counts_and_timestamps() {
         let temp = assert_fs::TempDir::new().unwrap();
-        temp.child("file").touch().unwrap();
+        let file = temp.child("file");
+        file.touch().unwrap();
+        fs::hard_link(file.path(), temp.child("hard_link").path()).unwrap();
+
+        let entry = Entry::from_path(file.path()).unwrap();
+        let metadata = fs::metadata(file.path()).unwrap();
 
-        let mut read_dir = fs::read_dir(temp.path()).unwrap().into_iter();
-        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap());
-        assert_eq!(
-            file_entry.display(&display_options),
-            "[F]─[M: now           ]─[file]"
-        );
+        assert_eq!(entry.nlink, 2);
+        assert_eq!(entry.inode, metadata.ino());
+        assert_eq!(entry.atime, metadata.accessed().ok());
+        assert!(entry.ctime.is_some_and(|ctime| ctime >= entry.mtime));
     }
 
     #[test]
-    fn it_should_display_created_ts() {
-        let display_options = DisplayOptions {
-            show_emoji_icon: false,
-            show_modified_ts: false,
-            show_created_ts: true,
-            show_size: false,
-            show_permissions: false,
-        };
-
+    fn it_should_read_symlink_targets() {
         let temp = assert_fs::TempDir::new().unwrap();
-        temp.child("file").touch().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("link").symlink_to_dir("dir").unwrap();
+        temp.child("dangling").symlink_to_file("missing").unwrap();
 
-        let mut read_dir = fs::read_dir(temp.path()).unwrap().into_iter();
-        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap());
-        assert_eq!(
-            file_entry.display(&display_options),
-            "[F]─[C: now           ]─[file]"
-        );
-    }
+        let mut entries = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| Entry::from_dir_entry(&e.unwrap()).unwrap())
+            .filter(|e| e.kind == EntryKind::Symlink)
+            .collect::<Vec<Entry>>();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
 
-    #[test]
-    fn it_should_display_size() {
-        let display_options = DisplayOptions {
-            show_emoji_icon: false,
-            show_modified_ts: false,
-            show_created_ts: false,
-            show_size: true,
-            show_permissions: false,
-        };
+        assert_eq!(entries[0].link_target, Some(PathBuf::from("missing")));
+        assert!(entries[0].is_broken_link);
+        assert_eq!(entries[1].link_target, Some(PathBuf::from("dir")));
+        assert!(!entries[1].is_broken_link);
 
-        let temp = assert_fs::TempDir::new().unwrap();
-        temp.child("file").touch().unwrap();
+        for entry in entries.iter_mut() {
+            entry.follow_link().unwrap();
+        }
 
-        let mut read_dir = fs::read_dir(temp.path()).unwrap().into_iter();
-        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap());
-        assert_eq!(
-            file_entry.display(&display_options),
-            "[F]─[S: 0 B       ]─[file]"
-        );
+        assert_eq!(entries[0].kind, EntryKind::Symlink);
+        assert_eq!(entries[1].kind, EntryKind::Dir);
+        assert_eq!(entries[1].link_target, Some(PathBuf::from("dir")));
     }
 
     #[test]
-    fn it_should_display_default_icons() {
-        let display_options = DisplayOptions {
-            show_emoji_icon: false,
-            show_modified_ts: false,
-            show_created_ts: false,
-            show_size: false,
-            show_permissions: false,
-        };
+    fn it_should_only_mark_missing_link_targets_as_broken() {
+        use std::fs::Permissions;
 
         let temp = assert_fs::TempDir::new().unwrap();
         temp.child("file").touch().unwrap();
-        temp.child("dir").create_dir_all().unwrap();
-        temp.child("symlink")
-            .symlink_to_file(temp.child("file").path())
+        temp.child("locked/target").touch().unwrap();
+        temp.child("not_dir")
+            .symlink_to_file("file/missing")
+            .unwrap();
+        temp.child("denied")
+            .symlink_to_file("locked/target")
             .unwrap();
 
-        let mut actual_display = String::new();
+        let not_dir = Entry::from_path(temp.child("not_dir").path()).unwrap();
+        assert!(not_dir.is_broken_link);
 
-        for entry in fs::read_dir(temp.path()).unwrap().into_iter() {
-            let entry = Entry::from_dir_entry(&entry.unwrap());
-            actual_display.push_str(&entry.display(&display_options));
-            actual_display.push('\n');
+        if uzers::get_effective_uid() == 0 {
+            return;
         }
 
-        assert!(actual_display.contains("[F]─[file]"));
-        assert!(actual_display.contains("[D]─[dir]"));
-        assert!(actual_display.contains("[L]─[symlink]"));
+        let locked = temp.child("locked");
+        fs::set_permissions(locked.path(), Permissions::from_mode(0o000)).unwrap();
+        let denied = Entry::from_path(temp.child("denied").path());
+        fs::set_permissions(locked.path(), Permissions::from_mode(0o755)).unwrap();
+
+        assert!(matches!(denied, Err(Error::Entry { .. })));
     }
 
     #[test]
-    fn it_should_display_emoji_icons() {
-        let display_options = DisplayOptions {
-            show_emoji_icon: true,
-            show_modified_ts: false,
-            show_created_ts: false,
-            show_size: false,
-            show_permissions: false,
-        };
-
+    fn it_should_create_entries_for_special_files() {
         let temp = assert_fs::TempDir::new().unwrap();
-        temp.child("file").touch().unwrap();
-        temp.child("dir").create_dir_all().unwrap();
-        temp.child("symlink")
-            .symlink_to_file(temp.child("file").path())
+        let _listener = UnixListener::bind(temp.child("socket").path()).unwrap();
+        Command::new("mkfifo")
+            .arg(temp.child("fifo").path())
+            .status()
             .unwrap();
 
-        let mut actual_display = String::new();
+        let entries = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| Entry::from_dir_entry(&e.unwrap()).unwrap())
+            .collect::<Vec<Entry>>();
 
-        for entry in fs::read_dir(temp.path()).unwrap().into_iter() {
-            let entry = Entry::from_dir_entry(&entry.unwrap());
-            actual_display.push_str(&entry.display(&display_options));
-            actual_display.push('\n');
-        }
+        assert!(entries
+            .iter()
+            .any(|e| e.name == "socket" && e.kind == EntryKind::Socket));
+        assert!(entries
+            .iter()
+            .any(|e| e.name == "fifo" && e.kind == EntryKind::Fifo));
+    }
+
+    #[test]
+    fn it_should_convert_device_file_types() {
+        let null = fs::symlink_metadata("/dev/null").unwrap();
 
-        assert!(actual_display.contains("[📄]─[file]"));
-        assert!(actual_display.contains("[📁]─[dir]"));
-        assert!(actual_display.contains("[🔗]─[symlink]"));
+        assert_eq!(EntryKind::from(null.file_type()), EntryKind::CharDevice);
+    }
+
+    #[test]
+    fn it_should_parse_entry_kinds() {
+        assert_eq!("d", EntryKind::Dir.to_string());
+        assert_eq!("f", EntryKind::File.to_string());
+        assert_eq!("l", EntryKind::Symlink.to_string());
+        assert_eq!("p", EntryKind::Fifo.to_string());
+        assert_eq!("s", EntryKind::Socket.to_string());
+        assert_eq!("b", EntryKind::BlockDevice.to_string());
+        assert_eq!("c", EntryKind::CharDevice.to_string());
+        assert_eq!("?", EntryKind::Unknown.to_string());
     }
 }
@@ -0,0 +1,157 @@
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rayon::ThreadPoolBuildError;
+
+pub type ErrorSink = Arc<dyn Fn(&Error) + Send + Sync>;
+
+#[derive(Debug)]
+pub enum Error {
+    ReadDir { path: PathBuf, source: io::Error },
+    Entry { path: PathBuf, source: io::Error },
+    Incomplete { failed: usize, code: i32 },
+    Pattern { pattern: String, message: String },
+    Loop { path: PathBuf },
+    ThreadPool(ThreadPoolBuildError),
+    Write(io::Error),
+}
+
+impl Error {
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::ReadDir { path, .. } | Error::Entry { path, .. } | Error::Loop { path } => {
+                Some(path)
+            }
+            Error::Incomplete { .. }
+            | Error::Pattern { .. }
+            | Error::ThreadPool(_)
+            | Error::Write(_) => None,
+        }
+    }
+
+    pub(crate) fn nested(self) -> Self {
+        match self {
+            Error::ReadDir { path, source } => Error::Entry { path, source },
+            error => error,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::ReadDir { .. }
+            | Error::Pattern { .. }
+            | Error::ThreadPool(_)
+            | Error::Write(_) => 2,
+            Error::Entry { .. } | Error::Loop { .. } => 1,
+            Error::Incomplete { code, .. } => *code,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::ReadDir { path, source } => {
+                write!(
+                    f,
+                    "Failed to read directory '{}': {}",
+                    path.display(),
+                    source
+                )
+            }
+            Error::Entry { path, source } => {
+                write!(f, "cannot access '{}': {}", path.display(), source)
+            }
+            Error::Incomplete { failed, .. } => {
+                write!(f, "{} entries could not be listed", failed)
+            }
+            Error::Pattern { pattern, message } => {
+                write!(f, "invalid pattern '{}': {}", pattern, message)
+            }
+            Error::Loop { path } => {
+                write!(f, "not following filesystem loop at '{}'", path.display())
+            }
+            Error::ThreadPool(source) => {
+                write!(f, "failed to start worker threads: {}", source)
+            }
+            Error::Write(source) => write!(f, "write error: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ReadDir { source, .. } | Error::Entry { source, .. } | Error::Write(source) => {
+                Some(source)
+            }
+            Error::ThreadPool(source) => Some(source),
+            Error::Incomplete { .. } | Error::Pattern { .. } | Error::Loop { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_map_errors_to_exit_codes() {
+        let read_dir = Error::ReadDir {
+            path: PathBuf::from("dir"),
+            source: io::Error::from(io::ErrorKind::NotFound),
+        };
+        let entry = Error::Entry {
+            path: PathBuf::from("dir/file"),
+            source: io::Error::from(io::ErrorKind::PermissionDenied),
+        };
+        let incomplete = Error::Incomplete { failed: 2, code: 1 };
+        let file_loop = Error::Loop {
+            path: PathBuf::from("dir/link"),
+        };
+        let pattern = Error::Pattern {
+            pattern: String::from("[a"),
+            message: String::from("unclosed character class"),
+        };
+
+        assert_eq!(read_dir.path(), Some(Path::new("dir")));
+        assert_eq!(incomplete.path(), None);
+        assert_eq!(file_loop.path(), Some(Path::new("dir/link")));
+        assert_eq!(read_dir.exit_code(), 2);
+        assert_eq!(pattern.exit_code(), 2);
+        assert_eq!(entry.exit_code(), 1);
+        assert_eq!(incomplete.exit_code(), 1);
+        assert_eq!(file_loop.exit_code(), 1);
+    }
+
+    #[test]
+    fn it_should_display_errors() {
+        let entry = Error::Entry {
+            path: PathBuf::from("dir/file"),
+            source: io::Error::from(io::ErrorKind::PermissionDenied),
+        };
+
+        assert!(entry.to_string().starts_with("cannot access 'dir/file': "));
+        assert_eq!(
+            Error::Incomplete { failed: 2, code: 1 }.to_string(),
+            "2 entries could not be listed"
+        );
+        assert_eq!(
+            Error::Pattern {
+                pattern: String::from("[a"),
+                message: String::from("unclosed character class"),
+            }
+            .to_string(),
+            "invalid pattern '[a': unclosed character class"
+        );
+        assert_eq!(
+            Error::Loop {
+                path: PathBuf::from("dir/link"),
+            }
+            .to_string(),
+            "not following filesystem loop at 'dir/link'"
+        );
+    }
+}
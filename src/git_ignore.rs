@@ -0,0 +1,183 @@
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+struct Matchers {
+    matcher: Gitignore,
+    parent: Option<Arc<Matchers>>,
+}
+
+#[derive(Clone)]
+pub(crate) struct GitIgnore {
+    dir: PathBuf,
+    matchers: Option<Arc<Matchers>>,
+}
+
+impl GitIgnore {
+    pub(crate) fn new(path: &Path) -> Self {
+        let dir = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mut matchers = vec![];
+
+        match dir
+            .ancestors()
+            .find(|ancestor| ancestor.join(".git").exists())
+        {
+            Some(repo_root) => {
+                let (global, _) = GitignoreBuilder::new(repo_root).build_global();
+                matchers.push(global);
+                matchers.extend(Self::matcher(
+                    repo_root,
+                    &[repo_root.join(".git").join("info").join("exclude")],
+                ));
+
+                let mut ancestors = dir
+                    .ancestors()
+                    .take_while(|ancestor| ancestor.starts_with(repo_root))
+                    .collect::<Vec<_>>();
+                ancestors.reverse();
+                matchers.extend(ancestors.into_iter().filter_map(Self::dir_matcher));
+            }
+            None => matchers.extend(Self::dir_matcher(&dir)),
+        }
+
+        GitIgnore {
+            dir,
+            matchers: matchers.into_iter().fold(None, Self::push),
+        }
+    }
+
+    fn push(parent: Option<Arc<Matchers>>, matcher: Gitignore) -> Option<Arc<Matchers>> {
+        Some(Arc::new(Matchers { matcher, parent }))
+    }
+
+    fn matcher(root: &Path, files: &[PathBuf]) -> Option<Gitignore> {
+        let mut builder = GitignoreBuilder::new(root);
+        for file in files.iter().filter(|file| file.is_file()) {
+            builder.add(file);
+        }
+
+        builder.build().ok().filter(|matcher| !matcher.is_empty())
+    }
+
+    fn dir_matcher(dir: &Path) -> Option<Gitignore> {
+        let files = IGNORE_FILES
+            .iter()
+            .map(|file| dir.join(file))
+            .collect::<Vec<_>>();
+
+        Self::matcher(dir, &files)
+    }
+
+    pub(crate) fn child(&self, name: &str) -> Self {
+        let dir = self.dir.join(name);
+        let matchers = match Self::dir_matcher(&dir) {
+            Some(matcher) => Self::push(self.matchers.clone(), matcher),
+            None => self.matchers.clone(),
+        };
+
+        GitIgnore { dir, matchers }
+    }
+
+    pub(crate) fn is_ignored(&self, name: &str, is_dir: bool) -> bool {
+        let path = self.dir.join(name);
+
+        iter::successors(self.matchers.as_deref(), |matchers| {
+            matchers.parent.as_deref()
+        })
+        .map(|matchers| matchers.matcher.matched(&path, is_dir))
+        .find(|matched| !matched.is_none())
+        .is_some_and(|matched| matches!(matched, Match::Ignore(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn it_should_ignore_entries_from_ignore_files() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child(".gitignore")
+            .write_str("target/\n*.log\n!keep.log\n")
+            .unwrap();
+        temp.child(".ignore").write_str("notes.txt\n").unwrap();
+
+        let git_ignore = GitIgnore::new(temp.path());
+
+        assert!(git_ignore.is_ignored("target", true));
+        assert!(!git_ignore.is_ignored("target", false));
+        assert!(git_ignore.is_ignored("debug.log", false));
+        assert!(!git_ignore.is_ignored("keep.log", false));
+        assert!(git_ignore.is_ignored("notes.txt", false));
+        assert!(!git_ignore.is_ignored("main.rs", false));
+    }
+
+    #[test]
+    fn it_should_apply_nested_ignore_files() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child(".gitignore")
+            .write_str("*.log\n/build\n")
+            .unwrap();
+        temp.child("src/.gitignore")
+            .write_str("!debug.log\ngenerated\n")
+            .unwrap();
+
+        let root = GitIgnore::new(temp.path());
+        let src = root.child("src");
+
+        assert!(root.is_ignored("build", true));
+        assert!(!src.is_ignored("build", true));
+        assert!(src.is_ignored("trace.log", false));
+        assert!(!src.is_ignored("debug.log", false));
+        assert!(src.is_ignored("generated", false));
+        assert!(!root.is_ignored("generated", false));
+    }
+
+    #[test]
+    fn it_should_share_matchers_with_the_parent_directory() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child(".gitignore").write_str("*.log\n").unwrap();
+        temp.child("src/lib/.gitignore")
+            .write_str("!debug.log\n")
+            .unwrap();
+
+        let root = GitIgnore::new(temp.path());
+        let src = root.child("src");
+        let lib = src.child("lib");
+
+        let is_same = |a: Option<&Arc<Matchers>>, b: Option<&Arc<Matchers>>| {
+            a.zip(b).is_some_and(|(a, b)| Arc::ptr_eq(a, b))
+        };
+        let lib_parent = lib.matchers.as_ref().and_then(|m| m.parent.as_ref());
+
+        assert!(is_same(src.matchers.as_ref(), root.matchers.as_ref()));
+        assert!(is_same(lib_parent, root.matchers.as_ref()));
+        assert!(src.is_ignored("debug.log", false));
+        assert!(!lib.is_ignored("debug.log", false));
+    }
+
+    #[test]
+    fn it_should_load_ignore_files_up_to_the_repository_root() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child(".git/info/exclude")
+            .write_str("secret\n")
+            .unwrap();
+        temp.child(".gitignore").write_str("*.tmp\n").unwrap();
+        temp.child("src/lib/.gitignore")
+            .write_str("!keep.tmp\n")
+            .unwrap();
+        temp.child("src/lib").create_dir_all().unwrap();
+
+        let git_ignore = GitIgnore::new(&temp.path().join("src"));
+
+        assert!(git_ignore.is_ignored("secret", false));
+        assert!(git_ignore.is_ignored("cache.tmp", false));
+        assert!(!git_ignore.child("lib").is_ignored("keep.tmp", false));
+    }
+}
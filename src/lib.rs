@@ -1,35 +1,316 @@
 mod args;
+mod collate;
 mod entries_handler;
 mod entry;
+mod error;
+mod git_ignore;
+mod owner;
+mod parse;
+mod pattern;
+mod render;
+mod sort;
+mod walk;
+
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
 
 pub use args::Args;
-use entries_handler::EntriesHandler;
-use entry::DisplayOptions;
+pub use entries_handler::{EntriesHandler, TimeField};
+pub use entry::{Entry, EntryKind};
+pub use error::{Error, ErrorSink};
+pub use render::{
+    DelimitedRenderer, DisplayOptions, JsonRenderer, NdjsonRenderer, OutputFormat, Renderer,
+    TextRenderer, TimeStyle,
+};
+pub use sort::{GroupDirs, SortKey, SortSpec};
+pub use walk::{Walk, WalkEntry};
 
 //$[begin_cov_exclude]
-pub fn run_with_args(args: &Args) {
-    let entries_handler = EntriesHandler::new(args);
-    let display_options = DisplayOptions::from(args);
+pub fn run_with_args(args: &Args) -> Result<(), Error> {
+    let stdout = io::stdout();
+    let args = Args {
+        is_terminal: stdout.is_terminal(),
+        ..args.clone()
+    };
 
-    if args.recursive {
-        for entry in entries_handler.get_entries_recursive(&args.path).iter() {
-            entry.display_recursive(&display_options, 0);
-        }
+    if args.is_terminal {
+        run_with_writer(&args, &mut io::LineWriter::new(stdout.lock()))
     } else {
-        for entry in entries_handler.get_entries(&args.path).iter() {
-            println!("{}", entry.display(&display_options));
-        }
+        run_with_writer(&args, &mut io::BufWriter::new(stdout.lock()))
     }
 }
 //$[end_cov_exclude]
 
+pub fn run_with_writer(args: &Args, out: &mut dyn Write) -> Result<(), Error> {
+    let renderer = Box::<dyn Renderer>::from(args);
+
+    run_with_renderer(args, renderer.as_ref(), out)
+}
+
+pub fn run_with_renderer(
+    args: &Args,
+    renderer: &dyn Renderer,
+    out: &mut dyn Write,
+) -> Result<(), Error> {
+    let entries_handler = EntriesHandler::new(args)?;
+    let (files, dirs) = entries_handler.get_operands(&args.paths);
+
+    if args.format == OutputFormat::Text && args.paths.len() > 1 {
+        let mut is_first = files.is_empty();
+        if !is_first {
+            renderer
+                .render_stream(&mut Walk::materialized(&entries_handler, files), out)
+                .map_err(Error::Write)?;
+        }
+
+        for dir in dirs {
+            let Some(mut entries) = list_dir(
+                &entries_handler,
+                dir,
+                args.recursive,
+                renderer.is_streaming(),
+            ) else {
+                continue;
+            };
+            if !is_first {
+                writeln!(out).map_err(Error::Write)?;
+            }
+            writeln!(out, "{}:", dir.display()).map_err(Error::Write)?;
+            renderer
+                .render_stream(&mut entries, out)
+                .map_err(Error::Write)?;
+            is_first = false;
+        }
+    } else {
+        let mut entries = Walk::materialized(&entries_handler, files).chain(
+            dirs.into_iter()
+                .filter_map(|dir| {
+                    list_dir(
+                        &entries_handler,
+                        dir,
+                        args.recursive,
+                        renderer.is_streaming(),
+                    )
+                })
+                .flatten(),
+        );
+        renderer
+            .render_stream(&mut entries, out)
+            .map_err(Error::Write)?;
+    }
+    out.flush().map_err(Error::Write)?;
+
+    entries_handler.take_result()
+}
+
+fn list_dir<'a>(
+    entries_handler: &'a EntriesHandler,
+    path: &Path,
+    recursive: bool,
+    is_streaming: bool,
+) -> Option<Walk<'a>> {
+    let entries = match (recursive, is_streaming) {
+        (true, true) => entries_handler.walk(path),
+        (true, false) => entries_handler
+            .get_entries_recursive(path)
+            .map(|entries| Walk::materialized(entries_handler, entries)),
+        (false, _) => entries_handler
+            .get_entries(path)
+            .map(|entries| Walk::materialized(entries_handler, entries)),
+    };
+
+    entries.map_err(|error| entries_handler.report(error)).ok()
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use assert_cmd::prelude::*;
     use assert_fs::prelude::*;
     use predicates::prelude::*;
     use std::process::Command;
 
+    #[test]
+    fn it_should_capture_output_in_writer() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+
+        let args = Args {
+            paths: vec![temp.path().to_path_buf()],
+            ..Args::default()
+        };
+        let mut out = vec![];
+        run_with_writer(&args, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "[F]─[file]\n");
+    }
+
+    #[test]
+    fn it_should_render_with_custom_renderer() {
+        struct NameRenderer;
+
+        impl Renderer for NameRenderer {
+            fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+                for entry in entries.iter() {
+                    write!(out, "{};", entry.name)?;
+                }
+                Ok(())
+            }
+        }
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file1").touch().unwrap();
+        temp.child("file2").touch().unwrap();
+
+        let args = Args {
+            paths: vec![temp.path().to_path_buf()],
+            ..Args::default()
+        };
+        let mut out = vec![];
+        run_with_renderer(&args, &NameRenderer, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "file1;file2;");
+    }
+
+    #[test]
+    fn it_should_sort_names_naturally() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("log10").touch().unwrap();
+        temp.child("log9").touch().unwrap();
+
+        cmd.arg(temp.path()).arg("-1").arg("--sort=v");
+        cmd.assert().success().stdout("[F]─[log9]\n[F]─[log10]\n");
+    }
+
+    #[test]
+    fn it_should_sort_by_several_keys_with_directories_first() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("small").write_str("1").unwrap();
+        temp.child("large").write_str("1234567890").unwrap();
+        temp.child("other").write_str("1").unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1")
+            .arg("--sort=size:desc,name")
+            .arg("--group-dirs=first");
+        cmd.assert()
+            .success()
+            .stdout("[D]─[dir]\n[F]─[large]\n[F]─[other]\n[F]─[small]\n");
+    }
+
+    #[test]
+    fn it_should_sort_by_link_count_and_extension() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("b.txt").touch().unwrap();
+        temp.child("c.rs").touch().unwrap();
+        temp.child("a.txt").touch().unwrap();
+        std::fs::hard_link(temp.child("a.txt").path(), temp.child("z.rs").path()).unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1")
+            .arg("--links")
+            .arg("--sort=h:desc,x");
+        cmd.assert().success().stdout(
+            "[F]─[L: 2]─[z.rs]\n[F]─[L: 2]─[a.txt]\n[F]─[L: 1]─[c.rs]\n[F]─[L: 1]─[b.txt]\n",
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_sort_keys() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg("--sort=name,bogus");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid sort key 'bogus'"));
+    }
+
+    #[test]
+    fn it_should_list_each_path_under_a_header() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir1/a").touch().unwrap();
+        temp.child("dir2/b").touch().unwrap();
+        temp.child("file").touch().unwrap();
+
+        cmd.current_dir(temp.path())
+            .arg("-1")
+            .arg("dir2")
+            .arg("file")
+            .arg("dir1");
+        cmd.assert()
+            .success()
+            .stdout("[F]─[file]\n\ndir2:\n[F]─[b]\n\ndir1:\n[F]─[a]\n");
+    }
+
+    #[test]
+    fn it_should_list_files_given_as_paths() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").write_str("1234567890").unwrap();
+
+        cmd.current_dir(temp.path()).arg("-1S").arg("file");
+        cmd.assert()
+            .success()
+            .stdout("[F]─[S: 10 B      ]─[file]\n");
+    }
+
+    #[test]
+    fn it_should_list_broken_symlinks_given_as_paths() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("broken").symlink_to_file("missing").unwrap();
+
+        cmd.current_dir(temp.path()).arg("-1").arg("broken");
+        cmd.assert()
+            .success()
+            .stdout("[L]─[broken -> missing (broken)]\n");
+    }
+
+    #[test]
+    fn it_should_keep_listing_after_a_path_fails() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir/a").touch().unwrap();
+
+        cmd.current_dir(temp.path())
+            .arg("-1")
+            .arg("missing")
+            .arg("dir");
+        cmd.assert()
+            .code(2)
+            .stdout("dir:\n[F]─[a]\n")
+            .stderr(predicate::str::contains("'missing'"));
+    }
+
+    #[test]
+    fn it_should_merge_paths_in_machine_readable_formats() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir1/a").touch().unwrap();
+        temp.child("dir2/b").touch().unwrap();
+
+        cmd.current_dir(temp.path())
+            .arg("--format=csv")
+            .arg("dir1")
+            .arg("dir2");
+        cmd.assert()
+            .success()
+            .stdout("kind,name,path,depth\nfile,a,dir1/a,0\nfile,b,dir2/b,0\n");
+    }
+
     #[test]
     fn it_should_throw_when_path_does_not_exist() {
         let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
@@ -37,6 +318,7 @@ mod tests {
         cmd.arg("./this/path/does/not/exist");
         cmd.assert()
             .failure()
+            .code(2)
             .stderr(predicate::str::contains("Failed to read directory"));
     }
 
@@ -70,6 +352,402 @@ mod tests {
             .stdout(predicate::str::contains(".hidden"));
     }
 
+    #[test]
+    fn it_should_output_long_listing() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("empty").touch().unwrap();
+        temp.child("full").write_str("1234567890").unwrap();
+
+        cmd.arg(temp.path()).arg("-lS");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("F  0 B empty\n"))
+            .stdout(predicate::str::contains("F 10 B full\n"));
+    }
+
+    #[test]
+    fn it_should_limit_recursion_depth() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir/nested/deep").create_dir_all().unwrap();
+        temp.child("dir/nested/file").touch().unwrap();
+
+        cmd.arg(temp.path()).arg("-R").arg("-L").arg("2");
+        cmd.assert()
+            .success()
+            .stdout("[D]─[dir]\n└── [D]─[nested]─[… 2 more entries]\n");
+    }
+
+    #[test]
+    fn it_should_display_symlink_targets() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+        std::os::unix::fs::symlink("file", temp.child("link").path()).unwrap();
+        std::os::unix::fs::symlink("missing", temp.child("dangling").path()).unwrap();
+
+        cmd.arg(temp.path()).arg("-1");
+        cmd.assert()
+            .success()
+            .stdout("[L]─[dangling -> missing (broken)]\n[F]─[file]\n[L]─[link -> file]\n");
+    }
+
+    #[test]
+    fn it_should_follow_symlinks_without_looping() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir/file").touch().unwrap();
+        std::os::unix::fs::symlink("..", temp.child("dir/loop").path()).unwrap();
+
+        cmd.arg(temp.path()).arg("-R").arg("--follow");
+        cmd.assert()
+            .code(1)
+            .stdout("[D]─[dir]\n├── [F]─[file]\n└── [D]─[loop -> ..]\n")
+            .stderr(predicate::str::contains("not following filesystem loop at"));
+    }
+
+    #[test]
+    fn it_should_keep_listing_past_an_unreadable_directory() {
+        use std::fs::{self, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        if uzers::get_effective_uid() == 0 {
+            eprintln!("skipped: permission bits are not enforced for root");
+            return;
+        }
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("a").touch().unwrap();
+        temp.child("locked/secret").touch().unwrap();
+        temp.child("z").touch().unwrap();
+        let locked = temp.child("locked");
+        fs::set_permissions(locked.path(), Permissions::from_mode(0o000)).unwrap();
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+        cmd.arg(temp.path()).arg("-R");
+        let assert = cmd.assert();
+
+        let args = Args {
+            paths: vec![temp.path().to_path_buf()],
+            recursive: true,
+            ..Args::default()
+        };
+        let result = run_with_writer(&args, &mut vec![]);
+        fs::set_permissions(locked.path(), Permissions::from_mode(0o755)).unwrap();
+
+        assert
+            .code(1)
+            .stdout("[F]─[a]\n[D]─[locked]\n[F]─[z]\n")
+            .stderr(predicate::str::contains("sb: cannot access"));
+        assert!(matches!(
+            result,
+            Err(Error::Incomplete { failed: 1, code: 1 })
+        ));
+    }
+
+    #[test]
+    fn it_should_send_errors_to_the_error_sink() {
+        use std::sync::{Arc, Mutex};
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir/a").touch().unwrap();
+
+        let errors = Arc::new(Mutex::new(vec![]));
+        let sink = Arc::clone(&errors);
+        let args = Args {
+            paths: vec![temp.path().join("missing"), temp.path().join("dir")],
+            one_per_line: true,
+            error_sink: Some(Arc::new(move |error: &Error| {
+                sink.lock().unwrap().push(error.to_string())
+            })),
+            ..Args::default()
+        };
+        let mut out = vec![];
+        let result = run_with_writer(&args, &mut out);
+
+        assert!(String::from_utf8(out).unwrap().ends_with("[F]─[a]\n"));
+        assert!(matches!(
+            result,
+            Err(Error::Incomplete { failed: 1, code: 2 })
+        ));
+        assert_eq!(errors.lock().unwrap().len(), 1);
+        assert!(errors.lock().unwrap()[0].contains("missing"));
+    }
+
+    #[test]
+    fn it_should_require_recursion_for_depth() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg("--depth").arg("2");
+        cmd.assert().failure();
+    }
+
+    #[test]
+    fn it_should_respect_git_ignore() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child(".gitignore").write_str("*.log\n").unwrap();
+        temp.child("debug.log").touch().unwrap();
+        temp.child("main.rs").touch().unwrap();
+
+        cmd.arg(temp.path()).arg("-1").arg("--git-ignore");
+        cmd.assert().success().stdout("[F]─[main.rs]\n");
+    }
+
+    #[test]
+    fn it_should_filter_by_patterns() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("main.rs").touch().unwrap();
+        temp.child("main.rs.bk").touch().unwrap();
+        temp.child("README.md").touch().unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1")
+            .arg("--include")
+            .arg("*.rs")
+            .arg("--include")
+            .arg("*.md");
+        cmd.assert()
+            .success()
+            .stdout("[F]─[README.md]\n[F]─[main.rs]\n");
+    }
+
+    #[test]
+    fn it_should_reject_invalid_patterns() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg("--exclude").arg("[a");
+        cmd.assert()
+            .failure()
+            .code(2)
+            .stderr(predicate::str::starts_with("sb: invalid pattern '[a'"));
+    }
+
+    #[test]
+    fn it_should_filter_by_size_and_time() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("empty").touch().unwrap();
+        temp.child("full").write_str("1234567890").unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1f")
+            .arg("--min-size=1K")
+            .arg("--newer=1h");
+        cmd.assert().success().stdout("");
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1f")
+            .arg("--min-size=10B")
+            .arg("--older=2000-01-01");
+        cmd.assert().success().stdout("");
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1f")
+            .arg("--max-size=0.01kb")
+            .arg("--newer=1h");
+        cmd.assert().success().stdout("[F]─[empty]\n[F]─[full]\n");
+    }
+
+    #[test]
+    fn it_should_find_nested_files_by_kind_and_size() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("a/b/big")
+            .write_str(&"x".repeat(10_000))
+            .unwrap();
+        temp.child("a/small").touch().unwrap();
+        temp.child("c/d").create_dir_all().unwrap();
+
+        cmd.arg(temp.path()).arg("-Rf").arg("--min-size=5000");
+        cmd.assert()
+            .success()
+            .stdout("[D]─[a]\n└── [D]─[b]\n    └── [F]─[big]\n");
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-R")
+            .arg("--type=f")
+            .arg("--format=json")
+            .arg("--min-size=5000");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\"big\""))
+            .stdout(predicate::str::contains("\"small\"").not());
+    }
+
+    #[test]
+    fn it_should_reject_invalid_sizes() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg("--min-size=10X");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("unknown size unit 'X'"));
+    }
+
+    #[test]
+    fn it_should_sort_directories_by_total_size() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("small/file").write_str("1234567890").unwrap();
+        temp.child("large/file")
+            .write_str(&"x".repeat(100_000))
+            .unwrap();
+
+        cmd.arg(temp.path())
+            .arg("-1r")
+            .arg("--sort=s")
+            .arg("--total-size");
+        cmd.assert().success().stdout("[D]─[large]\n[D]─[small]\n");
+    }
+
+    #[test]
+    fn it_should_color_output() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+        cmd.arg(temp.path())
+            .arg("--color=always")
+            .env("LS_COLORS", "di=01;31");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\x1b[01;31mdir\x1b[0m"));
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+        cmd.arg(temp.path()).arg("--color=never");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\x1b").not());
+    }
+
+    #[test]
+    fn it_should_output_json() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("dir/nested").touch().unwrap();
+
+        cmd.arg(temp.path()).arg("-R").arg("--format=json");
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(value[0]["name"], "dir");
+        assert_eq!(value[0]["kind"], "dir");
+        assert_eq!(value[0]["children"][0]["name"], "nested");
+    }
+
+    #[test]
+    fn it_should_output_ndjson() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("dir/nested").touch().unwrap();
+
+        cmd.arg(temp.path()).arg("-R").arg("--format=ndjson");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\"name\":\"dir\""))
+            .stdout(predicate::str::contains("\"depth\":1"))
+            .stdout(predicate::function(|s: &str| s.lines().count() == 2));
+    }
+
+    #[test]
+    fn it_should_display_owner_and_group() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+
+        cmd.arg(temp.path())
+            .arg("--owner")
+            .arg("--group")
+            .arg("--format=csv");
+        cmd.assert().success().stdout(predicate::str::starts_with(
+            "kind,name,path,depth,owner,group\n",
+        ));
+    }
+
+    #[test]
+    fn it_should_reject_unknown_owners() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg("--owned-by=no-such-user-here");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("unknown user 'no-such-user-here'"));
+    }
+
+    #[test]
+    fn it_should_format_timestamps_with_a_time_style() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.touch().unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(file.path())
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000))
+            .unwrap();
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+        cmd.arg(temp.path())
+            .arg("-1m")
+            .arg("--time-style=long-iso")
+            .arg("--utc");
+        cmd.assert()
+            .success()
+            .stdout("[F]─[M: 2023-11-14 22:13]─[file]\n");
+
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+        cmd.arg(temp.path()).arg("-lm").arg("--time-style=+%s");
+        cmd.assert().success().stdout("F 1700000000 file\n");
+    }
+
+    #[test]
+    fn it_should_reject_unknown_time_styles() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        cmd.arg("--time-style=full-iso");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid time style 'full-iso'"));
+    }
+
+    #[test]
+    fn it_should_output_csv() {
+        let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("dir/with,comma").touch().unwrap();
+
+        cmd.arg(temp.path()).arg("-RS").arg("--format=csv");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::starts_with("kind,name,path,depth,size\n"))
+            .stdout(predicate::str::contains("file,\"with,comma\","));
+    }
+
     #[test]
     fn it_should_display_emoji_icons() {
         let mut cmd = Command::cargo_bin("shikibetsu").unwrap();
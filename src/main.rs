@@ -1,8 +1,29 @@
+use std::io::ErrorKind;
+use std::sync::Arc;
+
 use clap::Parser;
-use shikibetsu::Args;
+use shikibetsu::{Args, Error};
 
 fn main() {
-    let args = Args::parse();
+    // SAFETY: called before any other thread exists, so nothing can be reading
+    // the locale while it changes.
+    unsafe {
+        libc::setlocale(libc::LC_COLLATE, c"".as_ptr());
+    }
+
+    let args = Args {
+        error_sink: Some(Arc::new(|error| eprintln!("sb: {}", error))),
+        ..Args::parse()
+    };
 
-    shikibetsu::run_with_args(&args);
+    match shikibetsu::run_with_args(&args) {
+        Ok(()) => {}
+        Err(Error::Write(error)) if error.kind() == ErrorKind::BrokenPipe => {}
+        Err(error) => {
+            if !matches!(error, Error::Incomplete { .. }) {
+                eprintln!("sb: {}", error);
+            }
+            std::process::exit(error.exit_code());
+        }
+    }
 }
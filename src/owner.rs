@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::sync::{OnceLock, RwLock};
+
+static USER_NAMES: OnceLock<RwLock<HashMap<u32, String>>> = OnceLock::new();
+static GROUP_NAMES: OnceLock<RwLock<HashMap<u32, String>>> = OnceLock::new();
+
+fn cached_name(
+    cache: &OnceLock<RwLock<HashMap<u32, String>>>,
+    id: u32,
+    lookup: impl FnOnce(u32) -> Option<String>,
+) -> String {
+    let names = cache.get_or_init(|| RwLock::new(HashMap::new()));
+
+    if let Some(name) = names.read().unwrap().get(&id) {
+        return name.clone();
+    }
+
+    let name = lookup(id).unwrap_or_else(|| id.to_string());
+    names.write().unwrap().entry(id).or_insert(name).clone()
+}
+
+fn to_string(name: &OsStr) -> String {
+    name.to_string_lossy().to_string()
+}
+
+pub(crate) fn user_name(uid: u32) -> String {
+    cached_name(&USER_NAMES, uid, |uid| {
+        uzers::get_user_by_uid(uid).map(|user| to_string(user.name()))
+    })
+}
+
+pub(crate) fn group_name(gid: u32) -> String {
+    cached_name(&GROUP_NAMES, gid, |gid| {
+        uzers::get_group_by_gid(gid).map(|group| to_string(group.name()))
+    })
+}
+
+pub(crate) fn user_id(name: &str) -> Option<u32> {
+    name.parse()
+        .ok()
+        .or_else(|| uzers::get_user_by_name(name).map(|user| user.uid()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_resolve_user_and_group_names() {
+        assert_eq!(user_name(0), "root");
+        assert_eq!(group_name(0), "root");
+        assert_eq!(user_id("root"), Some(0));
+        assert_eq!(user_id("1234"), Some(1234));
+    }
+
+    #[test]
+    fn it_should_fall_back_to_numeric_ids() {
+        assert_eq!(user_name(4_000_000_000), "4000000000");
+        assert_eq!(group_name(4_000_000_000), "4000000000");
+        assert_eq!(user_id("no-such-user-here"), None);
+    }
+}
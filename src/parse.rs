@@ -0,0 +1,135 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+
+use crate::owner::user_id;
+
+const SIZE_UNITS: [(&str, u64); 13] = [
+    ("B", 1),
+    ("K", 1_000),
+    ("KB", 1_000),
+    ("KIB", 1 << 10),
+    ("M", 1_000_000),
+    ("MB", 1_000_000),
+    ("MIB", 1 << 20),
+    ("G", 1_000_000_000),
+    ("GB", 1_000_000_000),
+    ("GIB", 1 << 30),
+    ("T", 1_000_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("TIB", 1 << 40),
+];
+
+pub(crate) fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let number = number
+        .parse::<f64>()
+        .map_err(|_| format!("invalid size '{}'", value))?;
+    let multiplier = match unit.trim().to_uppercase().as_str() {
+        "" => 1,
+        unit => SIZE_UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .map(|(_, multiplier)| *multiplier)
+            .ok_or_else(|| format!("unknown size unit '{}'", unit))?,
+    };
+
+    Ok((number * multiplier as f64).round() as u64)
+}
+
+pub(crate) fn parse_time(value: &str) -> Result<SystemTime, String> {
+    if let Ok(duration) = humantime::parse_duration(value) {
+        return SystemTime::now()
+            .checked_sub(duration)
+            .ok_or_else(|| format!("duration '{}' is too large", value));
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.into());
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .map_err(|_| {
+            format!(
+                "invalid time '{}': expected a duration (e.g. 2d, 3h) or a date (e.g. 2024-01-31)",
+                value
+            )
+        })?;
+
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(|| format!("invalid local time '{}'", value))
+}
+
+pub(crate) fn parse_user(value: &str) -> Result<u32, String> {
+    user_id(value).ok_or_else(|| format!("unknown user '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn it_should_parse_sizes() {
+        assert_eq!(parse_size("42"), Ok(42));
+        assert_eq!(parse_size("42B"), Ok(42));
+        assert_eq!(parse_size("10K"), Ok(10_000));
+        assert_eq!(parse_size("10M"), Ok(10_000_000));
+        assert_eq!(parse_size("10mb"), Ok(10_000_000));
+        assert_eq!(parse_size("1KiB"), Ok(1_024));
+        assert_eq!(parse_size("1.5GiB"), Ok(1_610_612_736));
+        assert_eq!(parse_size("2 TB"), Ok(2_000_000_000_000));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_sizes() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("-1").is_err());
+    }
+
+    #[test]
+    fn it_should_parse_durations_as_times_in_the_past() {
+        let time = parse_time("2d").unwrap();
+        let elapsed = time.elapsed().unwrap();
+
+        assert!(elapsed >= Duration::from_secs(2 * 24 * 60 * 60));
+        assert!(elapsed < Duration::from_secs(2 * 24 * 60 * 60 + 60));
+        assert!(parse_time("3h 30m").is_ok());
+    }
+
+    #[test]
+    fn it_should_parse_dates() {
+        assert_eq!(
+            parse_time("2023-11-14T22:13:20Z"),
+            Ok(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(parse_time("2024-01-31").is_ok());
+        assert!(parse_time("2024-01-31 12:30:00").is_ok());
+        assert!(parse_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn it_should_parse_users() {
+        assert_eq!(parse_user("root"), Ok(0));
+        assert_eq!(parse_user("1000"), Ok(1000));
+        assert_eq!(
+            parse_user("no-such-user-here"),
+            Err(String::from("unknown user 'no-such-user-here'"))
+        );
+    }
+}
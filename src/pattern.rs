@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+
+use crate::error::Error;
+
+enum Matcher {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+pub(crate) struct Pattern {
+    matcher: Matcher,
+    match_path: bool,
+}
+
+impl Pattern {
+    pub(crate) fn new(pattern: &str, is_regex: bool) -> Result<Self, Error> {
+        let to_error = |message: String| Error::Pattern {
+            pattern: pattern.to_string(),
+            message,
+        };
+
+        let matcher = if is_regex {
+            Matcher::Regex(Regex::new(pattern).map_err(|error| to_error(error.to_string()))?)
+        } else {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|error| to_error(error.kind().to_string()))?;
+            Matcher::Glob(glob.compile_matcher())
+        };
+
+        Ok(Pattern {
+            matcher,
+            match_path: pattern.contains('/'),
+        })
+    }
+
+    pub(crate) fn is_match(&self, relative_path: &Path) -> bool {
+        let candidate = if self.match_path {
+            relative_path.to_string_lossy()
+        } else {
+            relative_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+        };
+
+        match &self.matcher {
+            Matcher::Glob(glob) => glob.is_match(candidate.as_ref()),
+            Matcher::Regex(regex) => regex.is_match(&candidate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_match_globs_against_names() {
+        let pattern = Pattern::new("*.rs", false).unwrap();
+
+        assert!(pattern.is_match(Path::new("main.rs")));
+        assert!(pattern.is_match(Path::new("src/render/text.rs")));
+        assert!(!pattern.is_match(Path::new("main.rs.bk")));
+    }
+
+    #[test]
+    fn it_should_match_globs_against_relative_paths() {
+        let pattern = Pattern::new("src/**/*.rs", false).unwrap();
+
+        assert!(pattern.is_match(Path::new("src/main.rs")));
+        assert!(pattern.is_match(Path::new("src/render/text.rs")));
+        assert!(!pattern.is_match(Path::new("benches/main.rs")));
+
+        let pattern = Pattern::new("src/*.rs", false).unwrap();
+
+        assert!(pattern.is_match(Path::new("src/main.rs")));
+        assert!(!pattern.is_match(Path::new("src/render/text.rs")));
+    }
+
+    #[test]
+    fn it_should_match_regexes() {
+        let pattern = Pattern::new(r"^test_.*\.py$", true).unwrap();
+
+        assert!(pattern.is_match(Path::new("tests/test_cli.py")));
+        assert!(!pattern.is_match(Path::new("tests/cli_test.py")));
+
+        let pattern = Pattern::new(r"^docs/", true).unwrap();
+
+        assert!(pattern.is_match(Path::new("docs/index.md")));
+        assert!(!pattern.is_match(Path::new("src/docs/index.md")));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_patterns() {
+        assert!(matches!(
+            Pattern::new("[a", false),
+            Err(Error::Pattern { .. })
+        ));
+        assert!(matches!(
+            Pattern::new("(a", true),
+            Err(Error::Pattern { .. })
+        ));
+    }
+}
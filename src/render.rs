@@ -0,0 +1,537 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use file_mode::Mode;
+
+use crate::args::Args;
+use crate::entry::{Entry, EntryKind};
+use crate::walk::{collect_tree, WalkEntry};
+
+mod color;
+mod delimited;
+mod grid;
+mod json;
+mod table;
+mod text;
+mod time;
+mod tree;
+
+pub use color::{ColorChoice, Theme};
+pub use delimited::DelimitedRenderer;
+pub use grid::{GridDirection, GridRenderer};
+pub use json::{JsonRenderer, NdjsonRenderer};
+pub use table::TableRenderer;
+pub use text::TextRenderer;
+pub use time::TimeStyle;
+pub use tree::TreeGuides;
+
+pub trait Renderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()>;
+
+    fn render_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = WalkEntry>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        self.render(&collect_tree(entries), out)
+    }
+
+    fn is_streaming(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone)]
+pub struct DisplayOptions {
+    pub show_emoji_icon: bool,
+    pub show_modified_ts: bool,
+    pub show_created_ts: bool,
+    pub show_size: bool,
+    pub show_permissions: bool,
+    pub show_octal: bool,
+    pub show_owner: bool,
+    pub show_group: bool,
+    pub show_inode: bool,
+    pub show_links: bool,
+    pub show_accessed_ts: bool,
+    pub show_changed_ts: bool,
+    pub time_style: TimeStyle,
+    pub utc: bool,
+    pub is_terminal: bool,
+    pub theme: Option<Theme>,
+    pub tree_guides: TreeGuides,
+}
+
+impl From<&Args> for DisplayOptions {
+    fn from(item: &Args) -> Self {
+        let show_defaults = item.long
+            && !(item.show_modified_ts
+                || item.show_created_ts
+                || item.show_size
+                || item.show_permissions
+                || item.show_octal
+                || item.show_owner
+                || item.show_group
+                || item.show_inode
+                || item.show_links
+                || item.show_accessed_ts
+                || item.show_changed_ts);
+
+        DisplayOptions {
+            show_emoji_icon: item.show_emoji_icon,
+            show_modified_ts: item.show_modified_ts || show_defaults,
+            show_created_ts: item.show_created_ts,
+            show_size: item.show_size || show_defaults,
+            show_permissions: item.show_permissions || show_defaults,
+            show_octal: item.show_octal,
+            show_owner: item.show_owner,
+            show_group: item.show_group,
+            show_inode: item.show_inode,
+            show_links: item.show_links,
+            show_accessed_ts: item.show_accessed_ts,
+            show_changed_ts: item.show_changed_ts,
+            time_style: item.time_style.clone(),
+            utc: item.utc,
+            is_terminal: item.is_terminal,
+            theme: match item.format {
+                OutputFormat::Text => item.color.theme(item.is_terminal),
+                _ => None,
+            },
+            tree_guides: if item.ascii {
+                TreeGuides::ASCII
+            } else {
+                TreeGuides::UNICODE
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
+//$[begin_cov_exclude]
+impl ValueEnum for OutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            OutputFormat::Text,
+            OutputFormat::Json,
+            OutputFormat::Ndjson,
+            OutputFormat::Csv,
+            OutputFormat::Tsv,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            OutputFormat::Text => Some(PossibleValue::new("text")),
+            OutputFormat::Json => Some(PossibleValue::new("json")),
+            OutputFormat::Ndjson => Some(PossibleValue::new("ndjson")),
+            OutputFormat::Csv => Some(PossibleValue::new("csv")),
+            OutputFormat::Tsv => Some(PossibleValue::new("tsv")),
+        }
+    }
+}
+//$[end_cov_exclude]
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl DisplayOptions {
+    fn format_time(&self, time: Option<SystemTime>) -> String {
+        self.time_style.format(time, self.utc)
+    }
+
+    fn has_metadata(&self) -> bool {
+        self.show_modified_ts
+            || self.show_created_ts
+            || self.show_size
+            || self.show_permissions
+            || self.show_octal
+            || self.show_owner
+            || self.show_group
+            || self.show_inode
+            || self.show_links
+            || self.show_accessed_ts
+            || self.show_changed_ts
+    }
+}
+
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+//$[begin_cov_exclude]
+fn terminal_width() -> usize {
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return width as usize;
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+//$[end_cov_exclude]
+
+fn grid_direction(args: &Args, display_options: &DisplayOptions) -> Option<GridDirection> {
+    if args.across {
+        Some(GridDirection::Across)
+    } else if args.grid
+        || !(args.one_per_line
+            || args.long
+            || args.recursive
+            || display_options.has_metadata()
+            || !display_options.is_terminal)
+    {
+        Some(GridDirection::Down)
+    } else {
+        None
+    }
+}
+
+impl From<&Args> for Box<dyn Renderer> {
+    fn from(item: &Args) -> Self {
+        let display_options = DisplayOptions::from(item);
+
+        match item.format {
+            OutputFormat::Text => {
+                if item.long {
+                    Box::new(TableRenderer::new(display_options))
+                } else if let Some(direction) = grid_direction(item, &display_options) {
+                    Box::new(GridRenderer::new(
+                        display_options,
+                        terminal_width(),
+                        direction,
+                    ))
+                } else {
+                    Box::new(TextRenderer::new(display_options))
+                }
+            }
+            OutputFormat::Json => Box::new(JsonRenderer),
+            OutputFormat::Ndjson => Box::new(NdjsonRenderer),
+            OutputFormat::Csv => Box::new(DelimitedRenderer::csv(display_options)),
+            OutputFormat::Tsv => Box::new(DelimitedRenderer::tsv(display_options)),
+        }
+    }
+}
+
+pub(crate) fn kind_name(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Dir => "dir",
+        EntryKind::File => "file",
+        EntryKind::Symlink => "symlink",
+        EntryKind::Fifo => "fifo",
+        EntryKind::Socket => "socket",
+        EntryKind::BlockDevice => "block_device",
+        EntryKind::CharDevice => "char_device",
+        EntryKind::Unknown => "unknown",
+    }
+}
+
+pub(crate) fn format_elapsed(time: Option<SystemTime>) -> String {
+    match time {
+        Some(time) => timeago::Formatter::new().convert(time.elapsed().unwrap_or_default()),
+        None => String::from("-"),
+    }
+}
+
+pub(crate) fn format_mode(mode: u32) -> String {
+    Mode::from(mode).to_string()
+}
+
+pub(crate) fn format_octal_mode(mode: u32) -> String {
+    format!("{:04o}", mode & 0o7777)
+}
+
+pub(crate) fn format_truncated(entry: &Entry) -> Option<String> {
+    match entry.truncated {
+        Some(0) | None => None,
+        Some(1) => Some(String::from("… 1 more entry")),
+        Some(count) => Some(format!("… {} more entries", count)),
+    }
+}
+
+pub(crate) fn format_name(theme: Option<&Theme>, entry: &Entry) -> String {
+    let name = color::paint_entry(theme, entry, &entry.name);
+
+    match &entry.link_target {
+        Some(target) if entry.is_broken_link => format!(
+            "{} -> {} (broken)",
+            name,
+            color::paint(theme, "or", &target.to_string_lossy())
+        ),
+        Some(target) => format!("{} -> {}", name, target.to_string_lossy()),
+        None => name,
+    }
+}
+
+pub(crate) fn format_rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub(crate) fn epoch_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(error) => -(error.duration().as_secs() as i64),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    pub(crate) fn entry(name: &str, kind: EntryKind, children: Vec<Entry>) -> Entry {
+        Entry {
+            kind,
+            name: String::from(name),
+            path: PathBuf::from("/tmp").join(name),
+            size: 42,
+            blocks: 8,
+            mtime: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            btime: None,
+            ctime: None,
+            atime: None,
+            permissions: 0o100644,
+            device: 1,
+            inode: 1,
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            link_target: None,
+            is_broken_link: false,
+            children,
+            truncated: None,
+        }
+    }
+
+    pub(crate) fn render_to_string(renderer: &dyn Renderer, entries: &[Entry]) -> String {
+        let mut out = vec![];
+        renderer.render(entries, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn flatten(entries: Vec<Entry>, depth: usize, walk_entries: &mut Vec<WalkEntry>) {
+        let count = entries.len();
+
+        for (i, mut entry) in entries.into_iter().enumerate() {
+            let children = std::mem::take(&mut entry.children);
+            walk_entries.push(WalkEntry {
+                entry,
+                depth,
+                is_last: i + 1 == count,
+            });
+            flatten(children, depth + 1, walk_entries);
+        }
+    }
+
+    #[test]
+    fn it_should_stream_the_same_output_as_a_tree() {
+        let tree = || {
+            vec![
+                entry(
+                    "a",
+                    EntryKind::Dir,
+                    vec![
+                        entry(
+                            "b",
+                            EntryKind::Dir,
+                            vec![entry("c", EntryKind::File, vec![])],
+                        ),
+                        entry("d", EntryKind::File, vec![]),
+                    ],
+                ),
+                entry("e", EntryKind::File, vec![]),
+            ]
+        };
+        let display_options = DisplayOptions {
+            show_size: true,
+            ..DisplayOptions::from(&Args::default())
+        };
+        let renderers: Vec<Box<dyn Renderer>> = vec![
+            Box::new(TextRenderer::new(display_options.clone())),
+            Box::new(TableRenderer::new(display_options.clone())),
+            Box::new(JsonRenderer),
+            Box::new(NdjsonRenderer),
+            Box::new(DelimitedRenderer::csv(display_options)),
+        ];
+
+        for renderer in renderers.iter() {
+            let mut walk_entries = vec![];
+            flatten(tree(), 0, &mut walk_entries);
+
+            let mut out = vec![];
+            renderer
+                .render_stream(&mut walk_entries.into_iter(), &mut out)
+                .unwrap();
+
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                render_to_string(renderer.as_ref(), &tree())
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_create_display_options() {
+        let args = Args::default();
+        let display_options = DisplayOptions::from(&args);
+        assert!(!display_options.show_emoji_icon);
+        assert!(!display_options.show_modified_ts);
+        assert!(!display_options.show_created_ts);
+        assert!(!display_options.show_size);
+        assert!(!display_options.show_permissions);
+        assert_eq!(display_options.tree_guides, TreeGuides::UNICODE);
+
+        let args = Args {
+            ascii: true,
+            ..Args::default()
+        };
+        assert_eq!(DisplayOptions::from(&args).tree_guides, TreeGuides::ASCII);
+    }
+
+    #[test]
+    fn it_should_color_only_text_output() {
+        let args = Args {
+            color: ColorChoice::Always,
+            ..Args::default()
+        };
+        assert!(DisplayOptions::from(&args).theme.is_some());
+
+        let args = Args {
+            color: ColorChoice::Always,
+            format: OutputFormat::Json,
+            ..Args::default()
+        };
+        assert!(DisplayOptions::from(&args).theme.is_none());
+    }
+
+    #[test]
+    fn it_should_show_default_columns_in_long_listing() {
+        let args = Args {
+            long: true,
+            ..Args::default()
+        };
+        let display_options = DisplayOptions::from(&args);
+        assert!(display_options.show_modified_ts);
+        assert!(!display_options.show_created_ts);
+        assert!(display_options.show_size);
+        assert!(display_options.show_permissions);
+
+        let args = Args {
+            long: true,
+            show_size: true,
+            ..Args::default()
+        };
+        let display_options = DisplayOptions::from(&args);
+        assert!(!display_options.show_modified_ts);
+        assert!(display_options.show_size);
+        assert!(!display_options.show_permissions);
+    }
+
+    #[test]
+    fn it_should_choose_grid_direction() {
+        let display_options = DisplayOptions::from(&Args::default());
+
+        let args = Args {
+            grid: true,
+            ..Args::default()
+        };
+        assert_eq!(
+            grid_direction(&args, &display_options),
+            Some(GridDirection::Down)
+        );
+
+        let args = Args {
+            across: true,
+            ..Args::default()
+        };
+        assert_eq!(
+            grid_direction(&args, &display_options),
+            Some(GridDirection::Across)
+        );
+
+        let args = Args {
+            one_per_line: true,
+            ..Args::default()
+        };
+        assert_eq!(grid_direction(&args, &display_options), None);
+
+        let args = Args::default();
+        assert_eq!(grid_direction(&args, &display_options), None);
+
+        let args = Args {
+            is_terminal: true,
+            ..Args::default()
+        };
+        assert_eq!(
+            grid_direction(&args, &DisplayOptions::from(&args)),
+            Some(GridDirection::Down)
+        );
+    }
+
+    #[test]
+    fn it_should_parse_output_formats() {
+        assert_eq!("text", OutputFormat::Text.to_string());
+        assert_eq!("json", OutputFormat::Json.to_string());
+        assert_eq!("ndjson", OutputFormat::Ndjson.to_string());
+        assert_eq!("csv", OutputFormat::Csv.to_string());
+        assert_eq!("tsv", OutputFormat::Tsv.to_string());
+    }
+
+    #[test]
+    fn it_should_create_renderer_from_args() {
+        let args = Args {
+            format: OutputFormat::Csv,
+            ..Args::default()
+        };
+        let renderer = Box::<dyn Renderer>::from(&args);
+
+        let output = render_to_string(renderer.as_ref(), &[entry("file", EntryKind::File, vec![])]);
+
+        assert_eq!(output, "kind,name,path,depth\nfile,file,/tmp/file,0\n");
+    }
+
+    #[test]
+    fn it_should_format_truncated_entries() {
+        let mut dir = entry("dir", EntryKind::Dir, vec![]);
+        assert_eq!(format_truncated(&dir), None);
+
+        dir.truncated = Some(0);
+        assert_eq!(format_truncated(&dir), None);
+
+        dir.truncated = Some(1);
+        assert_eq!(format_truncated(&dir).unwrap(), "… 1 more entry");
+
+        dir.truncated = Some(12);
+        assert_eq!(format_truncated(&dir).unwrap(), "… 12 more entries");
+    }
+
+    #[test]
+    fn it_should_format_timestamps() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        assert_eq!(format_rfc3339(time), "2023-11-14T22:13:20Z");
+        assert_eq!(epoch_seconds(time), 1_700_000_000);
+        assert_eq!(epoch_seconds(UNIX_EPOCH - Duration::from_secs(10)), -10);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use unicode_width::UnicodeWidthStr;
+
+use crate::entry::{Entry, EntryKind};
+
+const DEFAULT_LS_COLORS: &str =
+    "di=01;34:ln=01;36:or=40;31;01:pi=40;33:so=01;35:bd=40;33;01:cd=40;33;01:\
+    ex=01;32:su=37;41:sg=30;43:tw=30;42:ow=34;42:st=37;44:\
+    inode=35:links=31:perms=33:owner=01;33:group=33:size=32:time=34";
+
+const SETUID: u32 = 0o4000;
+const SETGID: u32 = 0o2000;
+const STICKY: u32 = 0o1000;
+const OTHER_WRITABLE: u32 = 0o0002;
+const EXECUTABLE: u32 = 0o0111;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+//$[begin_cov_exclude]
+impl ValueEnum for ColorChoice {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[ColorChoice::Auto, ColorChoice::Always, ColorChoice::Never]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            ColorChoice::Auto => Some(PossibleValue::new("auto")),
+            ColorChoice::Always => Some(PossibleValue::new("always")),
+            ColorChoice::Never => Some(PossibleValue::new("never")),
+        }
+    }
+}
+//$[end_cov_exclude]
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl ColorChoice {
+    pub fn is_enabled(&self, no_color: Option<&str>, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && no_color.is_none_or(str::is_empty),
+        }
+    }
+
+    //$[begin_cov_exclude]
+    pub fn theme(&self, is_terminal: bool) -> Option<Theme> {
+        let no_color = std::env::var("NO_COLOR").ok();
+
+        if self.is_enabled(no_color.as_deref(), is_terminal) {
+            Some(Theme::from_ls_colors(
+                &std::env::var("LS_COLORS").unwrap_or_default(),
+            ))
+        } else {
+            None
+        }
+    }
+    //$[end_cov_exclude]
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    styles: HashMap<String, String>,
+    extensions: HashMap<String, String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::from_ls_colors("")
+    }
+}
+
+impl Theme {
+    pub fn from_ls_colors(ls_colors: &str) -> Self {
+        let mut theme = Theme {
+            styles: HashMap::new(),
+            extensions: HashMap::new(),
+        };
+
+        for definition in DEFAULT_LS_COLORS.split(':').chain(ls_colors.split(':')) {
+            let Some((key, style)) = definition.split_once('=') else {
+                continue;
+            };
+
+            match key.strip_prefix("*.") {
+                Some(extension) => {
+                    theme
+                        .extensions
+                        .insert(extension.to_lowercase(), style.to_string());
+                }
+                None => {
+                    theme.styles.insert(key.to_string(), style.to_string());
+                }
+            }
+        }
+
+        theme
+    }
+
+    fn style(&self, key: &str) -> Option<&str> {
+        self.styles
+            .get(key)
+            .map(String::as_str)
+            .filter(|style| !style.is_empty())
+    }
+
+    fn extension_style(&self, name: &str) -> Option<&str> {
+        let (_, extension) = name.rsplit_once('.')?;
+        self.extensions
+            .get(&extension.to_lowercase())
+            .map(String::as_str)
+    }
+
+    fn special_bits_style(&self, entry: &Entry) -> Option<&str> {
+        let mode = entry.permissions;
+
+        match entry.kind {
+            EntryKind::Dir if mode & STICKY != 0 && mode & OTHER_WRITABLE != 0 => self.style("tw"),
+            EntryKind::Dir if mode & OTHER_WRITABLE != 0 => self.style("ow"),
+            EntryKind::Dir if mode & STICKY != 0 => self.style("st"),
+            EntryKind::File if mode & SETUID != 0 => self.style("su"),
+            EntryKind::File if mode & SETGID != 0 => self.style("sg"),
+            EntryKind::File if mode & EXECUTABLE != 0 => self.style("ex"),
+            _ => None,
+        }
+    }
+
+    fn entry_style(&self, entry: &Entry) -> Option<&str> {
+        if let Some(style) = self.special_bits_style(entry) {
+            return Some(style);
+        }
+
+        match entry.kind {
+            EntryKind::Dir => self.style("di"),
+            EntryKind::File => self
+                .extension_style(&entry.name)
+                .or_else(|| self.style("fi")),
+            EntryKind::Symlink if entry.is_broken_link => self.style("or"),
+            EntryKind::Symlink => self.style("ln"),
+            EntryKind::Fifo => self.style("pi"),
+            EntryKind::Socket => self.style("so"),
+            EntryKind::BlockDevice => self.style("bd"),
+            EntryKind::CharDevice => self.style("cd"),
+            EntryKind::Unknown => None,
+        }
+    }
+
+    fn apply(style: Option<&str>, text: &str) -> String {
+        match style {
+            Some(style) => format!("\x1b[{}m{}\x1b[0m", style, text),
+            None => text.to_string(),
+        }
+    }
+
+    pub fn paint_entry(&self, entry: &Entry, text: &str) -> String {
+        Self::apply(self.entry_style(entry), text)
+    }
+
+    pub fn paint(&self, key: &str, text: &str) -> String {
+        Self::apply(self.style(key), text)
+    }
+}
+
+pub(crate) fn paint_entry(theme: Option<&Theme>, entry: &Entry, text: &str) -> String {
+    match theme {
+        Some(theme) => theme.paint_entry(entry, text),
+        None => text.to_string(),
+    }
+}
+
+pub(crate) fn paint(theme: Option<&Theme>, key: &str, text: &str) -> String {
+    match theme {
+        Some(theme) => theme.paint(key, text),
+        None => text.to_string(),
+    }
+}
+
+pub(crate) fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        rest = match rest[start..].find('m') {
+            Some(end) => &rest[start + end + 1..],
+            None => "",
+        };
+    }
+
+    width + rest.width()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::tests::entry;
+
+    #[test]
+    fn it_should_parse_color_choices() {
+        assert_eq!("auto", ColorChoice::Auto.to_string());
+        assert_eq!("always", ColorChoice::Always.to_string());
+        assert_eq!("never", ColorChoice::Never.to_string());
+    }
+
+    #[test]
+    fn it_should_decide_when_to_color() {
+        assert!(ColorChoice::Always.is_enabled(Some("1"), false));
+        assert!(!ColorChoice::Never.is_enabled(None, true));
+        assert!(ColorChoice::Auto.is_enabled(None, true));
+        assert!(ColorChoice::Auto.is_enabled(Some(""), true));
+        assert!(!ColorChoice::Auto.is_enabled(Some("1"), true));
+        assert!(!ColorChoice::Auto.is_enabled(None, false));
+    }
+
+    #[test]
+    fn it_should_color_entries_by_kind() {
+        let theme = Theme::default();
+
+        assert_eq!(
+            theme.paint_entry(&entry("dir", EntryKind::Dir, vec![]), "dir"),
+            "\x1b[01;34mdir\x1b[0m"
+        );
+        assert_eq!(
+            theme.paint_entry(&entry("link", EntryKind::Symlink, vec![]), "link"),
+            "\x1b[01;36mlink\x1b[0m"
+        );
+        assert_eq!(
+            theme.paint_entry(&entry("file", EntryKind::File, vec![]), "file"),
+            "file"
+        );
+    }
+
+    #[test]
+    fn it_should_color_executables_and_special_bits() {
+        let theme = Theme::default();
+
+        let mut executable = entry("run", EntryKind::File, vec![]);
+        executable.permissions = 0o100755;
+        assert_eq!(
+            theme.paint_entry(&executable, "run"),
+            "\x1b[01;32mrun\x1b[0m"
+        );
+
+        let mut setuid = entry("sudo", EntryKind::File, vec![]);
+        setuid.permissions = 0o104755;
+        assert_eq!(theme.paint_entry(&setuid, "sudo"), "\x1b[37;41msudo\x1b[0m");
+
+        let mut sticky = entry("tmp", EntryKind::Dir, vec![]);
+        sticky.permissions = 0o41777;
+        assert_eq!(theme.paint_entry(&sticky, "tmp"), "\x1b[30;42mtmp\x1b[0m");
+    }
+
+    #[test]
+    fn it_should_honor_ls_colors() {
+        let theme = Theme::from_ls_colors("di=01;31:fi=00:*.RS=01;33:ln=:size=35");
+
+        assert_eq!(
+            theme.paint_entry(&entry("dir", EntryKind::Dir, vec![]), "dir"),
+            "\x1b[01;31mdir\x1b[0m"
+        );
+        assert_eq!(
+            theme.paint_entry(&entry("main.rs", EntryKind::File, vec![]), "main.rs"),
+            "\x1b[01;33mmain.rs\x1b[0m"
+        );
+        assert_eq!(
+            theme.paint_entry(&entry("notes", EntryKind::File, vec![]), "notes"),
+            "\x1b[00mnotes\x1b[0m"
+        );
+        assert_eq!(
+            theme.paint_entry(&entry("link", EntryKind::Symlink, vec![]), "link"),
+            "link"
+        );
+        assert_eq!(theme.paint("size", "42 B"), "\x1b[35m42 B\x1b[0m");
+    }
+
+    #[test]
+    fn it_should_measure_visible_width() {
+        assert_eq!(visible_width("plain"), 5);
+        assert_eq!(visible_width("\x1b[01;34mdir\x1b[0m/"), 4);
+        assert_eq!(visible_width("\x1b[01;34m日本\x1b[0m"), 4);
+    }
+}
@@ -0,0 +1,274 @@
+use std::io::{self, Write};
+
+use super::{format_mode, format_octal_mode, format_rfc3339, kind_name, DisplayOptions, Renderer};
+use crate::entry::Entry;
+use crate::owner::{group_name, user_name};
+use crate::walk::WalkEntry;
+
+pub struct DelimitedRenderer {
+    display_options: DisplayOptions,
+    delimiter: &'static str,
+    escape: fn(&str) -> String,
+}
+
+impl DelimitedRenderer {
+    pub fn csv(display_options: DisplayOptions) -> Self {
+        Self {
+            display_options,
+            delimiter: ",",
+            escape: escape_csv_field,
+        }
+    }
+
+    pub fn tsv(display_options: DisplayOptions) -> Self {
+        Self {
+            display_options,
+            delimiter: "\t",
+            escape: escape_tsv_field,
+        }
+    }
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn escape_tsv_field(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn table_header(display_options: &DisplayOptions) -> Vec<&'static str> {
+    let mut header = vec!["kind", "name", "path", "depth"];
+
+    if display_options.show_inode {
+        header.push("inode");
+    }
+
+    if display_options.show_permissions {
+        header.push("permissions");
+    }
+
+    if display_options.show_octal {
+        header.push("octal");
+    }
+
+    if display_options.show_links {
+        header.push("links");
+    }
+
+    if display_options.show_owner {
+        header.push("owner");
+    }
+
+    if display_options.show_group {
+        header.push("group");
+    }
+
+    if display_options.show_size {
+        header.push("size");
+    }
+
+    if display_options.show_modified_ts {
+        header.push("mtime");
+    }
+
+    if display_options.show_created_ts {
+        header.push("btime");
+    }
+
+    if display_options.show_changed_ts {
+        header.push("ctime");
+    }
+
+    if display_options.show_accessed_ts {
+        header.push("atime");
+    }
+
+    header
+}
+
+fn table_record(entry: &Entry, depth: usize, display_options: &DisplayOptions) -> Vec<String> {
+    let mut record = vec![
+        kind_name(entry.kind).to_string(),
+        entry.name.clone(),
+        entry.path.to_string_lossy().to_string(),
+        depth.to_string(),
+    ];
+
+    if display_options.show_inode {
+        record.push(entry.inode.to_string());
+    }
+
+    if display_options.show_permissions {
+        record.push(format_mode(entry.permissions));
+    }
+
+    if display_options.show_octal {
+        record.push(format_octal_mode(entry.permissions));
+    }
+
+    if display_options.show_links {
+        record.push(entry.nlink.to_string());
+    }
+
+    if display_options.show_owner {
+        record.push(user_name(entry.uid));
+    }
+
+    if display_options.show_group {
+        record.push(group_name(entry.gid));
+    }
+
+    if display_options.show_size {
+        record.push(entry.size.to_string());
+    }
+
+    if display_options.show_modified_ts {
+        record.push(format_rfc3339(entry.mtime));
+    }
+
+    if display_options.show_created_ts {
+        record.push(entry.btime.map(format_rfc3339).unwrap_or_default());
+    }
+
+    if display_options.show_changed_ts {
+        record.push(entry.ctime.map(format_rfc3339).unwrap_or_default());
+    }
+
+    if display_options.show_accessed_ts {
+        record.push(entry.atime.map(format_rfc3339).unwrap_or_default());
+    }
+
+    record
+}
+
+impl DelimitedRenderer {
+    fn write_record<S: AsRef<str>>(&self, record: &[S], out: &mut dyn Write) -> io::Result<()> {
+        let fields = record
+            .iter()
+            .map(|field| (self.escape)(field.as_ref()))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", fields.join(self.delimiter))
+    }
+
+    fn write_records(&self, entry: &Entry, depth: usize, out: &mut dyn Write) -> io::Result<()> {
+        self.write_record(&table_record(entry, depth, &self.display_options), out)?;
+
+        for child in entry.children.iter() {
+            self.write_records(child, depth + 1, out)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for DelimitedRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        self.write_record(&table_header(&self.display_options), out)?;
+
+        for entry in entries.iter() {
+            self.write_records(entry, 0, out)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = WalkEntry>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        self.write_record(&table_header(&self.display_options), out)?;
+
+        for walk_entry in entries {
+            self.write_records(&walk_entry.entry, walk_entry.depth, out)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use crate::entry::EntryKind;
+    use crate::render::tests::{entry, render_to_string};
+
+    #[test]
+    fn it_should_escape_csv_fields() {
+        assert_eq!(escape_csv_field("plain"), "plain");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn it_should_escape_tsv_fields() {
+        assert_eq!(escape_tsv_field("plain"), "plain");
+        assert_eq!(escape_tsv_field("a\tb"), "a\\tb");
+        assert_eq!(escape_tsv_field("two\nlines"), "two\\nlines");
+        assert_eq!(escape_tsv_field("back\\slash"), "back\\\\slash");
+    }
+
+    #[test]
+    fn it_should_flatten_entries_to_csv() {
+        let renderer = DelimitedRenderer::csv(DisplayOptions {
+            show_modified_ts: true,
+            show_size: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+        let entries = vec![entry(
+            "dir",
+            EntryKind::Dir,
+            vec![entry("a,b", EntryKind::File, vec![])],
+        )];
+
+        let output = render_to_string(&renderer, &entries);
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "kind,name,path,depth,size,mtime",
+                "dir,dir,/tmp/dir,0,42,2023-11-14T22:13:20Z",
+                "file,\"a,b\",\"/tmp/a,b\",1,42,2023-11-14T22:13:20Z",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_flatten_entries_to_tsv() {
+        let renderer = DelimitedRenderer::tsv(DisplayOptions {
+            show_permissions: true,
+            show_inode: true,
+            show_links: true,
+            show_accessed_ts: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+        let entries = vec![entry("file", EntryKind::File, vec![])];
+
+        let output = render_to_string(&renderer, &entries);
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "kind\tname\tpath\tdepth\tinode\tpermissions\tlinks\tatime",
+                "file\tfile\t/tmp/file\t0\t1\t-rw-r--r--\t1\t",
+            ]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::io::{self, Write};
+
+use super::color::visible_width;
+use super::{DisplayOptions, Renderer, TextRenderer};
+use crate::entry::Entry;
+
+const SEPARATOR_WIDTH: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridDirection {
+    Down,
+    Across,
+}
+
+pub struct GridRenderer {
+    text_renderer: TextRenderer,
+    width: usize,
+    direction: GridDirection,
+}
+
+struct Layout {
+    rows: usize,
+    columns: usize,
+    widths: Vec<usize>,
+}
+
+impl GridRenderer {
+    pub fn new(display_options: DisplayOptions, width: usize, direction: GridDirection) -> Self {
+        Self {
+            text_renderer: TextRenderer::new(display_options),
+            width,
+            direction,
+        }
+    }
+
+    fn index(&self, row: usize, column: usize, layout: &Layout) -> usize {
+        match self.direction {
+            GridDirection::Down => column * layout.rows + row,
+            GridDirection::Across => row * layout.columns + column,
+        }
+    }
+
+    fn try_layout(&self, cell_widths: &[usize], rows: usize) -> Option<Layout> {
+        let columns = cell_widths.len().div_ceil(rows);
+        let rows = cell_widths.len().div_ceil(columns);
+        let mut layout = Layout {
+            rows,
+            columns,
+            widths: vec![0; columns],
+        };
+
+        for row in 0..rows {
+            for column in 0..columns {
+                if let Some(width) = cell_widths.get(self.index(row, column, &layout)) {
+                    layout.widths[column] = layout.widths[column].max(*width);
+                }
+            }
+        }
+
+        let total = layout.widths.iter().sum::<usize>() + SEPARATOR_WIDTH * (columns - 1);
+        if total <= self.width || rows == cell_widths.len() {
+            Some(layout)
+        } else {
+            None
+        }
+    }
+
+    fn min_rows(&self, cell_widths: &[usize]) -> usize {
+        let total = cell_widths
+            .iter()
+            .map(|width| width + SEPARATOR_WIDTH)
+            .sum::<usize>();
+
+        total
+            .div_ceil(self.width + SEPARATOR_WIDTH)
+            .clamp(1, cell_widths.len().max(1))
+    }
+
+    fn layout(&self, cell_widths: &[usize]) -> Layout {
+        (self.min_rows(cell_widths)..=cell_widths.len())
+            .find_map(|rows| self.try_layout(cell_widths, rows))
+            .unwrap_or(Layout {
+                rows: 0,
+                columns: 0,
+                widths: vec![],
+            })
+    }
+}
+
+impl Renderer for GridRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        let cells = entries
+            .iter()
+            .map(|entry| self.text_renderer.format_entry(entry))
+            .collect::<Vec<_>>();
+        let cell_widths = cells
+            .iter()
+            .map(|cell| visible_width(cell))
+            .collect::<Vec<_>>();
+        let layout = self.layout(&cell_widths);
+
+        for row in 0..layout.rows {
+            let mut line = String::new();
+
+            for column in 0..layout.columns {
+                let index = self.index(row, column, &layout);
+                let Some(cell) = cells.get(index) else {
+                    continue;
+                };
+
+                if column > 0 {
+                    let padding = layout.widths[column - 1]
+                        - cell_widths[self.index(row, column - 1, &layout)];
+                    line.push_str(&" ".repeat(padding + SEPARATOR_WIDTH));
+                }
+                line.push_str(cell);
+            }
+
+            writeln!(out, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use crate::entry::EntryKind;
+    use crate::render::tests::{entry, render_to_string};
+
+    fn display_options(show_emoji_icon: bool) -> DisplayOptions {
+        DisplayOptions {
+            show_emoji_icon,
+            ..DisplayOptions::from(&Args::default())
+        }
+    }
+
+    fn entries(names: &[&str]) -> Vec<Entry> {
+        names
+            .iter()
+            .map(|name| entry(name, EntryKind::File, vec![]))
+            .collect()
+    }
+
+    #[test]
+    fn it_should_lay_out_entries_down_columns() {
+        let renderer = GridRenderer::new(display_options(false), 25, GridDirection::Down);
+
+        assert_eq!(
+            render_to_string(&renderer, &entries(&["a", "bb", "c", "dddd", "e"])),
+            "[F]─[a]   [F]─[dddd]\n\
+             [F]─[bb]  [F]─[e]\n\
+             [F]─[c]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_lay_out_entries_across_rows() {
+        let renderer = GridRenderer::new(display_options(false), 25, GridDirection::Across);
+
+        assert_eq!(
+            render_to_string(&renderer, &entries(&["a", "bb", "c", "dddd", "e"])),
+            "[F]─[a]  [F]─[bb]\n\
+             [F]─[c]  [F]─[dddd]\n\
+             [F]─[e]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_fall_back_to_one_column() {
+        let renderer = GridRenderer::new(display_options(false), 5, GridDirection::Down);
+
+        assert_eq!(
+            render_to_string(&renderer, &entries(&["a", "b"])),
+            "[F]─[a]\n[F]─[b]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_measure_wide_characters() {
+        let renderer = GridRenderer::new(display_options(true), 24, GridDirection::Down);
+
+        assert_eq!(
+            render_to_string(&renderer, &entries(&["日本", "a", "b"])),
+            "[📄]─[日本]  [📄]─[b]\n\
+             [📄]─[a]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_start_from_the_narrowest_possible_layout() {
+        let renderer = GridRenderer::new(display_options(false), 80, GridDirection::Down);
+        let cell_widths = vec![5; 10_000];
+
+        assert_eq!(renderer.min_rows(&cell_widths), 854);
+
+        let layout = renderer.layout(&cell_widths);
+        assert_eq!(layout.columns, 11);
+        assert_eq!(layout.rows, 910);
+        assert_eq!(renderer.min_rows(&[]), 1);
+    }
+
+    #[test]
+    fn it_should_render_nothing_without_entries() {
+        let renderer = GridRenderer::new(display_options(false), 80, GridDirection::Down);
+
+        assert_eq!(render_to_string(&renderer, &[]), "");
+    }
+}
@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+
+use serde_json::{json, Value};
+
+use super::{epoch_seconds, format_mode, format_octal_mode, format_rfc3339, kind_name, Renderer};
+use crate::entry::Entry;
+use crate::owner::{group_name, user_name};
+use crate::walk::WalkEntry;
+
+pub struct JsonRenderer;
+
+pub struct NdjsonRenderer;
+
+fn to_json_fields(entry: &Entry) -> serde_json::Map<String, Value> {
+    let value = json!({
+        "kind": kind_name(entry.kind),
+        "name": entry.name,
+        "path": entry.path.to_string_lossy(),
+        "size": entry.size,
+        "mtime": format_rfc3339(entry.mtime),
+        "mtime_epoch": epoch_seconds(entry.mtime),
+        "btime": entry.btime.map(format_rfc3339),
+        "btime_epoch": entry.btime.map(epoch_seconds),
+        "ctime": entry.ctime.map(format_rfc3339),
+        "ctime_epoch": entry.ctime.map(epoch_seconds),
+        "atime": entry.atime.map(format_rfc3339),
+        "atime_epoch": entry.atime.map(epoch_seconds),
+        "permissions": format_mode(entry.permissions),
+        "octal": format_octal_mode(entry.permissions),
+        "mode": entry.permissions,
+        "inode": entry.inode,
+        "links": entry.nlink,
+        "uid": entry.uid,
+        "gid": entry.gid,
+        "owner": user_name(entry.uid),
+        "group": group_name(entry.gid),
+        "target": entry.link_target.as_ref().map(|target| target.to_string_lossy()),
+        "broken": entry.is_broken_link,
+        "truncated": entry.truncated,
+    });
+
+    match value {
+        Value::Object(fields) => fields,
+        _ => unreachable!(),
+    }
+}
+
+fn to_json(entry: &Entry) -> Value {
+    let mut fields = to_json_fields(entry);
+    fields.insert(
+        String::from("children"),
+        Value::Array(entry.children.iter().map(to_json).collect()),
+    );
+
+    Value::Object(fields)
+}
+
+fn write_ndjson(entry: &Entry, depth: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut fields = to_json_fields(entry);
+    fields.insert(String::from("depth"), json!(depth));
+    writeln!(out, "{}", Value::Object(fields))?;
+
+    for child in entry.children.iter() {
+        write_ndjson(child, depth + 1, out)?;
+    }
+
+    Ok(())
+}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        let entries = entries.iter().map(to_json).collect::<Vec<_>>();
+        serde_json::to_writer_pretty(&mut *out, &entries)?;
+        writeln!(out)
+    }
+}
+
+impl Renderer for NdjsonRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        for entry in entries.iter() {
+            write_ndjson(entry, 0, out)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = WalkEntry>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        for walk_entry in entries {
+            write_ndjson(&walk_entry.entry, walk_entry.depth, out)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::EntryKind;
+    use crate::render::tests::{entry, render_to_string};
+
+    #[test]
+    fn it_should_serialize_entry_to_json() {
+        let value = to_json(&entry(
+            "dir",
+            EntryKind::Dir,
+            vec![entry("file", EntryKind::File, vec![])],
+        ));
+
+        assert_eq!(value["kind"], "dir");
+        assert_eq!(value["name"], "dir");
+        assert_eq!(value["path"], "/tmp/dir");
+        assert_eq!(value["size"], 42);
+        assert_eq!(value["mtime"], "2023-11-14T22:13:20Z");
+        assert_eq!(value["mtime_epoch"], 1_700_000_000);
+        assert_eq!(value["btime"], Value::Null);
+        assert_eq!(value["ctime"], Value::Null);
+        assert_eq!(value["atime"], Value::Null);
+        assert_eq!(value["inode"], 1);
+        assert_eq!(value["links"], 1);
+        assert_eq!(value["permissions"], "-rw-r--r--");
+        assert_eq!(value["mode"], 0o100644);
+        assert_eq!(value["truncated"], Value::Null);
+        assert_eq!(value["children"][0]["name"], "file");
+        assert_eq!(value["children"][0]["children"], json!([]));
+    }
+
+    #[test]
+    fn it_should_serialize_entry_to_ndjson() {
+        let output = render_to_string(
+            &NdjsonRenderer,
+            &[entry(
+                "dir",
+                EntryKind::Dir,
+                vec![entry("file", EntryKind::File, vec![])],
+            )],
+        );
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2);
+
+        let child: Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(child["name"], "file");
+        assert_eq!(child["depth"], 1);
+        assert!(child.get("children").is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use std::io::{self, Write};
+
+use humansize::DECIMAL;
+
+use super::color::{paint, paint_entry, visible_width};
+use super::tree::walk;
+use super::{
+    format_mode, format_name, format_octal_mode, format_truncated, DisplayOptions, Renderer,
+};
+use crate::entry::Entry;
+use crate::owner::{group_name, user_name};
+
+pub struct TableRenderer {
+    display_options: DisplayOptions,
+}
+
+struct Row {
+    cells: Vec<String>,
+    name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    Icon,
+    Inode,
+    Permissions,
+    Octal,
+    Links,
+    Owner,
+    Group,
+    Size,
+    Modified,
+    Created,
+    Changed,
+    Accessed,
+}
+
+impl Column {
+    fn cell(&self, entry: &Entry, display_options: &DisplayOptions) -> String {
+        let theme = display_options.theme.as_ref();
+        let text = self.text(entry, display_options);
+
+        match self {
+            Column::Icon => paint_entry(theme, entry, &text),
+            Column::Inode => paint(theme, "inode", &text),
+            Column::Links => paint(theme, "links", &text),
+            Column::Permissions | Column::Octal => paint(theme, "perms", &text),
+            Column::Owner => paint(theme, "owner", &text),
+            Column::Group => paint(theme, "group", &text),
+            Column::Size => paint(theme, "size", &text),
+            Column::Modified | Column::Created | Column::Changed | Column::Accessed => {
+                paint(theme, "time", &text)
+            }
+        }
+    }
+
+    fn text(&self, entry: &Entry, display_options: &DisplayOptions) -> String {
+        match self {
+            Column::Icon => entry.get_icon(display_options.show_emoji_icon).to_string(),
+            Column::Inode => entry.inode.to_string(),
+            Column::Permissions => format_mode(entry.permissions),
+            Column::Octal => format_octal_mode(entry.permissions),
+            Column::Links => entry.nlink.to_string(),
+            Column::Owner => user_name(entry.uid),
+            Column::Group => group_name(entry.gid),
+            Column::Size => humansize::format_size(entry.size, DECIMAL),
+            Column::Modified => display_options.format_time(Some(entry.mtime)),
+            Column::Created => display_options.format_time(entry.btime),
+            Column::Changed => display_options.format_time(entry.ctime),
+            Column::Accessed => display_options.format_time(entry.atime),
+        }
+    }
+
+    fn is_right_aligned(&self) -> bool {
+        matches!(self, Column::Inode | Column::Links | Column::Size)
+    }
+}
+
+impl TableRenderer {
+    pub fn new(display_options: DisplayOptions) -> Self {
+        Self { display_options }
+    }
+
+    fn columns(&self) -> Vec<Column> {
+        let mut columns = vec![Column::Icon];
+
+        if self.display_options.show_inode {
+            columns.push(Column::Inode);
+        }
+
+        if self.display_options.show_permissions {
+            columns.push(Column::Permissions);
+        }
+
+        if self.display_options.show_octal {
+            columns.push(Column::Octal);
+        }
+
+        if self.display_options.show_links {
+            columns.push(Column::Links);
+        }
+
+        if self.display_options.show_owner {
+            columns.push(Column::Owner);
+        }
+
+        if self.display_options.show_group {
+            columns.push(Column::Group);
+        }
+
+        if self.display_options.show_size {
+            columns.push(Column::Size);
+        }
+
+        if self.display_options.show_modified_ts {
+            columns.push(Column::Modified);
+        }
+
+        if self.display_options.show_created_ts {
+            columns.push(Column::Created);
+        }
+
+        if self.display_options.show_changed_ts {
+            columns.push(Column::Changed);
+        }
+
+        if self.display_options.show_accessed_ts {
+            columns.push(Column::Accessed);
+        }
+
+        columns
+    }
+
+    fn row(&self, entry: &Entry, prefix: &str, columns: &[Column]) -> Row {
+        Row {
+            cells: columns
+                .iter()
+                .map(|column| column.cell(entry, &self.display_options))
+                .collect(),
+            name: format!(
+                "{}{}{}",
+                prefix,
+                format_name(self.display_options.theme.as_ref(), entry),
+                match format_truncated(entry) {
+                    Some(truncated) => format!(" ({})", truncated),
+                    None => String::new(),
+                }
+            ),
+        }
+    }
+}
+
+impl Renderer for TableRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        let columns = self.columns();
+
+        let mut rows = vec![];
+        walk::<io::Error>(
+            entries,
+            &self.display_options.tree_guides,
+            &mut |entry, prefix| {
+                rows.push(self.row(entry, prefix, &columns));
+                Ok(())
+            },
+        )?;
+
+        let mut widths = vec![0; columns.len()];
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.cells.iter()) {
+                *width = (*width).max(visible_width(cell));
+            }
+        }
+
+        for row in rows.iter() {
+            for ((column, width), cell) in columns.iter().zip(widths.iter()).zip(row.cells.iter()) {
+                let padding = " ".repeat(width - visible_width(cell));
+                if column.is_right_aligned() {
+                    write!(out, "{}{} ", padding, cell)?;
+                } else {
+                    write!(out, "{}{} ", cell, padding)?;
+                }
+            }
+            writeln!(out, "{}", row.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use crate::entry::EntryKind;
+    use crate::render::tests::{entry, render_to_string};
+    use crate::render::Theme;
+
+    #[test]
+    fn it_should_select_columns_from_display_options() {
+        let renderer = TableRenderer::new(DisplayOptions {
+            show_modified_ts: true,
+            show_created_ts: true,
+            show_size: true,
+            show_permissions: true,
+            show_octal: true,
+            show_owner: true,
+            show_group: true,
+            show_inode: true,
+            show_links: true,
+            show_accessed_ts: true,
+            show_changed_ts: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        assert_eq!(
+            renderer.columns(),
+            vec![
+                Column::Icon,
+                Column::Inode,
+                Column::Permissions,
+                Column::Octal,
+                Column::Links,
+                Column::Owner,
+                Column::Group,
+                Column::Size,
+                Column::Modified,
+                Column::Created,
+                Column::Changed,
+                Column::Accessed,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_align_columns() {
+        let renderer = TableRenderer::new(DisplayOptions {
+            show_created_ts: true,
+            show_size: true,
+            show_permissions: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let mut small = entry("small", EntryKind::File, vec![]);
+        small.size = 7;
+        let mut large = entry("large", EntryKind::File, vec![]);
+        large.size = 1_500_000;
+        large.permissions = 0o100755;
+        let dir = entry("dir", EntryKind::Dir, vec![small]);
+
+        assert_eq!(
+            render_to_string(&renderer, &[dir, large]),
+            "D -rw-r--r--    42 B - dir\n\
+             F -rw-r--r--     7 B - └── small\n\
+             F -rwxr-xr-x 1.50 MB - large\n"
+        );
+    }
+
+    #[test]
+    fn it_should_align_colored_columns() {
+        let renderer = TableRenderer::new(DisplayOptions {
+            show_size: true,
+            theme: Some(Theme::from_ls_colors("size=:di=34")),
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let mut small = entry("small", EntryKind::File, vec![]);
+        small.size = 7;
+        let dir = entry("dir", EntryKind::Dir, vec![]);
+
+        assert_eq!(
+            render_to_string(&renderer, &[dir, small]),
+            "\x1b[34mD\x1b[0m 42 B \x1b[34mdir\x1b[0m\n\
+             F  7 B small\n"
+        );
+    }
+
+    #[test]
+    fn it_should_render_icon_and_name_only() {
+        let renderer = TableRenderer::new(DisplayOptions {
+            show_emoji_icon: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        assert_eq!(
+            render_to_string(&renderer, &[entry("file", EntryKind::File, vec![])]),
+            "📄 file\n"
+        );
+    }
+}
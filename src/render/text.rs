@@ -0,0 +1,433 @@
+use std::io::{self, Write};
+
+use humansize::DECIMAL;
+
+use super::color::{paint, paint_entry};
+use super::tree::{walk, TreePrefix};
+use super::{
+    format_mode, format_name, format_octal_mode, format_truncated, DisplayOptions, Renderer,
+};
+use crate::entry::Entry;
+use crate::owner::{group_name, user_name};
+use crate::walk::WalkEntry;
+
+pub struct TextRenderer {
+    display_options: DisplayOptions,
+}
+
+impl TextRenderer {
+    pub fn new(display_options: DisplayOptions) -> Self {
+        Self { display_options }
+    }
+
+    fn format_st_mode(st_mode: u32) -> String {
+        let mode = format_mode(st_mode);
+        let mut result = String::with_capacity(13);
+
+        for (i, c) in mode.chars().enumerate() {
+            if i % 3 == 1 {
+                result.push('|');
+            }
+            result.push(c);
+        }
+
+        format!("[{}]", result)
+    }
+
+    pub fn format_entry(&self, entry: &Entry) -> String {
+        let display_options = &self.display_options;
+        let theme = display_options.theme.as_ref();
+        let mut metadata = vec![];
+
+        if display_options.show_inode {
+            metadata.push(paint(theme, "inode", &format!("[I: {}]", entry.inode)));
+        }
+
+        if display_options.show_permissions {
+            metadata.push(paint(
+                theme,
+                "perms",
+                &Self::format_st_mode(entry.permissions),
+            ));
+        }
+
+        if display_options.show_octal {
+            metadata.push(paint(
+                theme,
+                "perms",
+                &format!("[{}]", format_octal_mode(entry.permissions)),
+            ));
+        }
+
+        if display_options.show_links {
+            metadata.push(paint(theme, "links", &format!("[L: {}]", entry.nlink)));
+        }
+
+        if display_options.show_owner {
+            metadata.push(paint(
+                theme,
+                "owner",
+                &format!("[O: {}]", user_name(entry.uid)),
+            ));
+        }
+
+        if display_options.show_group {
+            metadata.push(paint(
+                theme,
+                "group",
+                &format!("[G: {}]", group_name(entry.gid)),
+            ));
+        }
+
+        if display_options.show_modified_ts {
+            metadata.push(paint(
+                theme,
+                "time",
+                &format!(
+                    "[M: {: <14}]",
+                    display_options.format_time(Some(entry.mtime))
+                ),
+            ));
+        }
+
+        if display_options.show_created_ts {
+            metadata.push(paint(
+                theme,
+                "time",
+                &format!("[B: {: <14}]", display_options.format_time(entry.btime)),
+            ));
+        }
+
+        if display_options.show_changed_ts {
+            metadata.push(paint(
+                theme,
+                "time",
+                &format!("[C: {: <14}]", display_options.format_time(entry.ctime)),
+            ));
+        }
+
+        if display_options.show_accessed_ts {
+            metadata.push(paint(
+                theme,
+                "time",
+                &format!("[A: {: <14}]", display_options.format_time(entry.atime)),
+            ));
+        }
+
+        if display_options.show_size {
+            metadata.push(paint(
+                theme,
+                "size",
+                &format!("[S: {: <10}]", humansize::format_size(entry.size, DECIMAL)),
+            ));
+        }
+
+        let metadata = metadata.iter().fold(String::new(), |acc, e| acc + "─" + e);
+        let truncated = match format_truncated(entry) {
+            Some(truncated) => format!("─[{}]", truncated),
+            None => String::new(),
+        };
+        format!(
+            "[{}]{}─[{}]{}",
+            paint_entry(
+                theme,
+                entry,
+                entry.get_icon(display_options.show_emoji_icon)
+            ),
+            metadata,
+            format_name(theme, entry),
+            truncated
+        )
+    }
+}
+
+impl Renderer for TextRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        walk(
+            entries,
+            &self.display_options.tree_guides,
+            &mut |entry, prefix| writeln!(out, "{}{}", prefix, self.format_entry(entry)),
+        )
+    }
+
+    fn render_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = WalkEntry>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let mut tree_prefix = TreePrefix::new(&self.display_options.tree_guides);
+
+        for walk_entry in entries {
+            let prefix = tree_prefix.next(walk_entry.depth, walk_entry.is_last);
+            writeln!(out, "{}{}", prefix, self.format_entry(&walk_entry.entry))?;
+        }
+
+        Ok(())
+    }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use crate::entry::EntryKind;
+    use crate::render::tests::{entry, render_to_string};
+    use crate::render::{Theme, TimeStyle};
+    use assert_fs::prelude::*;
+    use regex::Regex;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn it_should_format_st_mode() {
+        assert_eq!(TextRenderer::format_st_mode(0o100644), "[-|rw-|r--|r--]");
+        assert_eq!(TextRenderer::format_st_mode(0o40755), "[d|rwx|r-x|r-x]");
+        assert_eq!(TextRenderer::format_st_mode(0o120777), "[l|rwx|rwx|rwx]");
+        assert_eq!(TextRenderer::format_st_mode(0o104755), "[-|rws|r-x|r-x]");
+        assert_eq!(TextRenderer::format_st_mode(0o102644), "[-|rw-|r-S|r--]");
+        assert_eq!(TextRenderer::format_st_mode(0o41777), "[d|rwx|rwx|rwt]");
+        assert_eq!(TextRenderer::format_st_mode(0o41770), "[d|rwx|rwx|--T]");
+    }
+
+    #[test]
+    fn it_should_display_permissions() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_permissions: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+
+        let mut read_dir = fs::read_dir(temp.path()).unwrap();
+        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap()).unwrap();
+
+        let re = Regex::new(
+            r"^\[F\]─\[-\|(r|-)(w|-)(x|-)\|(r|-)(w|-)(x|-)\|(r|-)(w|-)(x|-)\]─\[file\]$",
+        )
+        .unwrap();
+
+        assert!(re.is_match(renderer.format_entry(&file_entry).as_str()));
+    }
+
+    #[test]
+    fn it_should_display_octal_modes() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_permissions: true,
+            show_octal: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+        let mut file_entry = entry("file", EntryKind::File, vec![]);
+        file_entry.permissions = 0o104755;
+
+        assert_eq!(
+            renderer.format_entry(&file_entry),
+            "[F]─[-|rws|r-x|r-x]─[4755]─[file]"
+        );
+    }
+
+    #[test]
+    fn it_should_display_modified_ts() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_modified_ts: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+
+        let mut read_dir = fs::read_dir(temp.path()).unwrap();
+        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            renderer.format_entry(&file_entry),
+            "[F]─[M: now           ]─[file]"
+        );
+    }
+
+    #[test]
+    fn it_should_display_created_ts() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_created_ts: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+
+        let mut read_dir = fs::read_dir(temp.path()).unwrap();
+        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            renderer.format_entry(&file_entry),
+            "[F]─[B: now           ]─[file]"
+        );
+    }
+
+    #[test]
+    fn it_should_display_size() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_size: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+
+        let mut read_dir = fs::read_dir(temp.path()).unwrap();
+        let file_entry = Entry::from_dir_entry(&read_dir.next().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            renderer.format_entry(&file_entry),
+            "[F]─[S: 0 B       ]─[file]"
+        );
+    }
+
+    #[test]
+    fn it_should_display_default_icons() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("symlink")
+            .symlink_to_file(temp.child("file").path())
+            .unwrap();
+
+        let mut actual_display = String::new();
+
+        for entry in fs::read_dir(temp.path()).unwrap() {
+            let entry = Entry::from_dir_entry(&entry.unwrap()).unwrap();
+            actual_display.push_str(&renderer.format_entry(&entry));
+            actual_display.push('\n');
+        }
+
+        assert!(actual_display.contains("[F]─[file]"));
+        assert!(actual_display.contains("[D]─[dir]"));
+        assert!(actual_display.contains("[L]─[symlink -> "));
+    }
+
+    #[test]
+    fn it_should_display_emoji_icons() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_emoji_icon: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file").touch().unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("symlink")
+            .symlink_to_file(temp.child("file").path())
+            .unwrap();
+
+        let mut actual_display = String::new();
+
+        for entry in fs::read_dir(temp.path()).unwrap() {
+            let entry = Entry::from_dir_entry(&entry.unwrap()).unwrap();
+            actual_display.push_str(&renderer.format_entry(&entry));
+            actual_display.push('\n');
+        }
+
+        assert!(actual_display.contains("[📄]─[file]"));
+        assert!(actual_display.contains("[📁]─[dir]"));
+        assert!(actual_display.contains("[🔗]─[symlink -> "));
+    }
+
+    #[test]
+    fn it_should_display_colors() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_size: true,
+            theme: Some(Theme::default()),
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        assert_eq!(
+            renderer.format_entry(&entry("dir", EntryKind::Dir, vec![])),
+            "[\x1b[01;34mD\x1b[0m]─\x1b[32m[S: 42 B      ]\x1b[0m─[\x1b[01;34mdir\x1b[0m]"
+        );
+    }
+
+    #[test]
+    fn it_should_render_children_as_tree() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            ..DisplayOptions::from(&Args::default())
+        });
+        let entries = vec![entry(
+            "dir",
+            EntryKind::Dir,
+            vec![
+                entry(
+                    "nested",
+                    EntryKind::Dir,
+                    vec![entry("file", EntryKind::File, vec![])],
+                ),
+                entry("last", EntryKind::File, vec![]),
+            ],
+        )];
+
+        assert_eq!(
+            render_to_string(&renderer, &entries),
+            "[D]─[dir]\n\
+             ├── [D]─[nested]\n\
+             │   └── [F]─[file]\n\
+             └── [F]─[last]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_display_symlink_targets() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            theme: Some(Theme::default()),
+            ..DisplayOptions::from(&Args::default())
+        });
+        let mut link = entry("link", EntryKind::Symlink, vec![]);
+        link.link_target = Some(PathBuf::from("missing"));
+        link.is_broken_link = true;
+
+        assert_eq!(
+            renderer.format_entry(&link),
+            "[\x1b[40;31;01mL\x1b[0m]─[\x1b[40;31;01mlink\x1b[0m -> \x1b[40;31;01mmissing\x1b[0m (broken)]"
+        );
+    }
+
+    #[test]
+    fn it_should_display_owner_and_group() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_owner: true,
+            show_group: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+
+        assert_eq!(
+            renderer.format_entry(&entry("file", EntryKind::File, vec![])),
+            "[F]─[O: root]─[G: root]─[file]"
+        );
+    }
+
+    #[test]
+    fn it_should_display_inodes_links_and_status_times() {
+        let renderer = TextRenderer::new(DisplayOptions {
+            show_permissions: true,
+            show_inode: true,
+            show_links: true,
+            show_accessed_ts: true,
+            show_changed_ts: true,
+            time_style: TimeStyle::Epoch,
+            utc: true,
+            ..DisplayOptions::from(&Args::default())
+        });
+        let mut file_entry = entry("file", EntryKind::File, vec![]);
+        file_entry.inode = 1234;
+        file_entry.nlink = 3;
+        file_entry.ctime = Some(file_entry.mtime);
+
+        assert_eq!(
+            renderer.format_entry(&file_entry),
+            "[F]─[I: 1234]─[-|rw-|r--|r--]─[L: 3]─[C: 1700000000    ]─[A: -             ]─[file]"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+
+use super::{epoch_seconds, format_elapsed};
+
+const SIX_MONTHS: Duration = Duration::from_secs(31_556_952 / 2);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeStyle {
+    Relative,
+    Iso,
+    LongIso,
+    Rfc3339,
+    Epoch,
+    Custom(String),
+}
+
+impl FromStr for TimeStyle {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "relative" => Ok(TimeStyle::Relative),
+            "iso" => Ok(TimeStyle::Iso),
+            "long-iso" => Ok(TimeStyle::LongIso),
+            "rfc3339" => Ok(TimeStyle::Rfc3339),
+            "epoch" => Ok(TimeStyle::Epoch),
+            _ => match value.strip_prefix('+') {
+                Some(format)
+                    if !StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) =>
+                {
+                    Ok(TimeStyle::Custom(format.to_string()))
+                }
+                Some(format) => Err(format!("invalid time format '{}'", format)),
+                None => Err(format!(
+                    "invalid time style '{}': expected relative, iso, long-iso, rfc3339, epoch or +FORMAT",
+                    value
+                )),
+            },
+        }
+    }
+}
+
+impl Display for TimeStyle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            TimeStyle::Relative => write!(f, "relative"),
+            TimeStyle::Iso => write!(f, "iso"),
+            TimeStyle::LongIso => write!(f, "long-iso"),
+            TimeStyle::Rfc3339 => write!(f, "rfc3339"),
+            TimeStyle::Epoch => write!(f, "epoch"),
+            TimeStyle::Custom(format) => write!(f, "+{}", format),
+        }
+    }
+}
+
+impl TimeStyle {
+    pub(crate) fn format(&self, time: Option<SystemTime>, utc: bool) -> String {
+        match time {
+            Some(time) if utc => self.format_in(DateTime::<Utc>::from(time), time),
+            Some(time) => self.format_in(DateTime::<Local>::from(time), time),
+            None => String::from("-"),
+        }
+    }
+
+    fn format_in<Tz: TimeZone>(&self, date_time: DateTime<Tz>, time: SystemTime) -> String
+    where
+        Tz::Offset: Display,
+    {
+        match self {
+            TimeStyle::Relative => format_elapsed(Some(time)),
+            TimeStyle::Iso if is_recent(time) => date_time.format("%m-%d %H:%M").to_string(),
+            TimeStyle::Iso => date_time.format("%Y-%m-%d").to_string(),
+            TimeStyle::LongIso => date_time.format("%Y-%m-%d %H:%M").to_string(),
+            TimeStyle::Rfc3339 => date_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            TimeStyle::Epoch => epoch_seconds(time).to_string(),
+            TimeStyle::Custom(format) => date_time.format(format).to_string(),
+        }
+    }
+}
+
+fn is_recent(time: SystemTime) -> bool {
+    time.elapsed().is_ok_and(|elapsed| elapsed < SIX_MONTHS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn it_should_parse_time_styles() {
+        assert_eq!("relative".parse(), Ok(TimeStyle::Relative));
+        assert_eq!("iso".parse(), Ok(TimeStyle::Iso));
+        assert_eq!("long-iso".parse(), Ok(TimeStyle::LongIso));
+        assert_eq!("rfc3339".parse(), Ok(TimeStyle::Rfc3339));
+        assert_eq!("epoch".parse(), Ok(TimeStyle::Epoch));
+        assert_eq!(
+            "+%Y/%m/%d".parse(),
+            Ok(TimeStyle::Custom(String::from("%Y/%m/%d")))
+        );
+        assert!("full-iso".parse::<TimeStyle>().is_err());
+        assert_eq!(
+            "+%Q".parse::<TimeStyle>(),
+            Err(String::from("invalid time format '%Q'"))
+        );
+    }
+
+    #[test]
+    fn it_should_format_times_in_utc() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let format = |style: &str| style.parse::<TimeStyle>().unwrap().format(Some(time), true);
+
+        assert_eq!(format("iso"), "2023-11-14");
+        assert_eq!(format("long-iso"), "2023-11-14 22:13");
+        assert_eq!(format("rfc3339"), "2023-11-14T22:13:20Z");
+        assert_eq!(format("epoch"), "1700000000");
+        assert_eq!(format("+%d.%m.%Y %H:%M:%S"), "14.11.2023 22:13:20");
+        assert_eq!(TimeStyle::Iso.format(None, true), "-");
+    }
+
+    #[test]
+    fn it_should_format_recent_times_without_the_year() {
+        let time = SystemTime::now() - Duration::from_secs(60);
+        let expected = DateTime::<Utc>::from(time)
+            .format("%m-%d %H:%M")
+            .to_string();
+
+        assert_eq!(TimeStyle::Iso.format(Some(time), true), expected);
+    }
+
+    #[test]
+    fn it_should_format_times_in_local_time() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let expected = DateTime::<Local>::from(time)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+
+        assert_eq!(TimeStyle::LongIso.format(Some(time), false), expected);
+    }
+}
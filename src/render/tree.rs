@@ -0,0 +1,136 @@
+use crate::entry::Entry;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TreeGuides {
+    pub branch: &'static str,
+    pub last: &'static str,
+    pub vertical: &'static str,
+    pub blank: &'static str,
+}
+
+impl TreeGuides {
+    pub const UNICODE: TreeGuides = TreeGuides {
+        branch: "├── ",
+        last: "└── ",
+        vertical: "│   ",
+        blank: "    ",
+    };
+
+    pub const ASCII: TreeGuides = TreeGuides {
+        branch: "|-- ",
+        last: "`-- ",
+        vertical: "|   ",
+        blank: "    ",
+    };
+}
+
+pub(crate) struct TreePrefix<'a> {
+    guides: &'a TreeGuides,
+    ancestors: Vec<bool>,
+}
+
+impl<'a> TreePrefix<'a> {
+    pub(crate) fn new(guides: &'a TreeGuides) -> Self {
+        TreePrefix {
+            guides,
+            ancestors: vec![],
+        }
+    }
+
+    pub(crate) fn next(&mut self, depth: usize, is_last: bool) -> String {
+        self.ancestors.truncate(depth);
+
+        let mut prefix = String::new();
+        if depth > 0 {
+            for ancestor_is_last in self.ancestors.iter().skip(1) {
+                prefix.push_str(if *ancestor_is_last {
+                    self.guides.blank
+                } else {
+                    self.guides.vertical
+                });
+            }
+            prefix.push_str(if is_last {
+                self.guides.last
+            } else {
+                self.guides.branch
+            });
+        }
+
+        self.ancestors.push(is_last);
+        prefix
+    }
+}
+
+pub(crate) fn walk<'a, E>(
+    entries: &'a [Entry],
+    guides: &TreeGuides,
+    visit: &mut dyn FnMut(&'a Entry, &str) -> Result<(), E>,
+) -> Result<(), E> {
+    walk_level(entries, 0, &mut TreePrefix::new(guides), visit)
+}
+
+fn walk_level<'a, E>(
+    entries: &'a [Entry],
+    depth: usize,
+    tree_prefix: &mut TreePrefix,
+    visit: &mut dyn FnMut(&'a Entry, &str) -> Result<(), E>,
+) -> Result<(), E> {
+    for (i, entry) in entries.iter().enumerate() {
+        let prefix = tree_prefix.next(depth, i + 1 == entries.len());
+        visit(entry, &prefix)?;
+        walk_level(&entry.children, depth + 1, tree_prefix, visit)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::EntryKind;
+    use crate::render::tests::entry;
+
+    fn tree() -> Vec<Entry> {
+        vec![
+            entry(
+                "a",
+                EntryKind::Dir,
+                vec![
+                    entry(
+                        "b",
+                        EntryKind::Dir,
+                        vec![entry("c", EntryKind::File, vec![])],
+                    ),
+                    entry("d", EntryKind::File, vec![]),
+                ],
+            ),
+            entry("e", EntryKind::File, vec![]),
+        ]
+    }
+
+    fn lines(guides: &TreeGuides) -> Vec<String> {
+        let mut lines = vec![];
+        walk::<()>(&tree(), guides, &mut |entry, prefix| {
+            lines.push(format!("{}{}", prefix, entry.name));
+            Ok(())
+        })
+        .unwrap();
+        lines
+    }
+
+    #[test]
+    fn it_should_walk_with_unicode_guides() {
+        assert_eq!(
+            lines(&TreeGuides::UNICODE),
+            vec!["a", "├── b", "│   └── c", "└── d", "e"]
+        );
+    }
+
+    #[test]
+    fn it_should_walk_with_ascii_guides() {
+        assert_eq!(
+            lines(&TreeGuides::ASCII),
+            vec!["a", "|-- b", "|   `-- c", "`-- d", "e"]
+        );
+    }
+}
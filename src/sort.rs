@@ -0,0 +1,554 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+
+use crate::args::Args;
+use crate::collate::{locale_key, natural_cmp};
+use crate::entry::{Entry, EntryKind};
+use crate::owner::user_name;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortKey {
+    Name,
+    CaseInsensitive,
+    Locale,
+    Natural,
+    Extension,
+    Kind,
+    Created,
+    Changed,
+    Modified,
+    Accessed,
+    Owner,
+    Size,
+    Inode,
+    Links,
+}
+
+//$[begin_cov_exclude]
+impl ValueEnum for SortKey {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            SortKey::Name,
+            SortKey::CaseInsensitive,
+            SortKey::Locale,
+            SortKey::Natural,
+            SortKey::Extension,
+            SortKey::Kind,
+            SortKey::Created,
+            SortKey::Changed,
+            SortKey::Modified,
+            SortKey::Accessed,
+            SortKey::Owner,
+            SortKey::Size,
+            SortKey::Inode,
+            SortKey::Links,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            SortKey::Name => Some(PossibleValue::new("n").alias("name")),
+            SortKey::CaseInsensitive => Some(PossibleValue::new("i").alias("iname")),
+            SortKey::Locale => Some(PossibleValue::new("l").alias("locale")),
+            SortKey::Natural => Some(PossibleValue::new("v").aliases(["natural", "version"])),
+            SortKey::Extension => Some(PossibleValue::new("x").aliases(["extension", "ext"])),
+            SortKey::Kind => Some(PossibleValue::new("k").alias("kind")),
+            SortKey::Created => {
+                Some(PossibleValue::new("b").aliases(["created", "btime", "birth"]))
+            }
+            SortKey::Changed => Some(PossibleValue::new("c").aliases(["ctime", "changed"])),
+            SortKey::Modified => Some(PossibleValue::new("m").aliases(["mtime", "modified"])),
+            SortKey::Accessed => Some(PossibleValue::new("a").aliases(["atime", "accessed"])),
+            SortKey::Owner => Some(PossibleValue::new("o").alias("owner")),
+            SortKey::Size => Some(PossibleValue::new("s").alias("size")),
+            SortKey::Inode => Some(PossibleValue::new("I").alias("inode")),
+            SortKey::Links => Some(PossibleValue::new("h").aliases(["links", "nlink"])),
+        }
+    }
+}
+//$[end_cov_exclude]
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            SortKey::Name => "n",
+            SortKey::CaseInsensitive => "i",
+            SortKey::Locale => "l",
+            SortKey::Natural => "v",
+            SortKey::Extension => "x",
+            SortKey::Kind => "k",
+            SortKey::Created => "b",
+            SortKey::Changed => "c",
+            SortKey::Modified => "m",
+            SortKey::Accessed => "a",
+            SortKey::Owner => "o",
+            SortKey::Size => "s",
+            SortKey::Inode => "I",
+            SortKey::Links => "h",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl SortKey {
+    fn value(&self, entry: &Entry) -> SortValue {
+        match self {
+            SortKey::Name => SortValue::Text(entry.name.clone()),
+            SortKey::CaseInsensitive => SortValue::Text(entry.name.to_lowercase()),
+            SortKey::Locale => SortValue::Bytes(locale_key(&entry.name)),
+            SortKey::Natural => SortValue::Natural(entry.name.clone()),
+            SortKey::Extension => SortValue::Text(extension(&entry.name)),
+            SortKey::Kind => SortValue::Number(kind_rank(entry.kind)),
+            SortKey::Created => SortValue::from(entry.btime),
+            SortKey::Changed => SortValue::from(entry.ctime),
+            SortKey::Modified => SortValue::Time(entry.mtime),
+            SortKey::Accessed => SortValue::from(entry.atime),
+            SortKey::Owner => SortValue::Text(user_name(entry.uid)),
+            SortKey::Size => SortValue::Number(entry.size),
+            SortKey::Inode => SortValue::Number(entry.inode),
+            SortKey::Links => SortValue::Number(entry.nlink),
+        }
+    }
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn kind_rank(kind: EntryKind) -> u64 {
+    match kind {
+        EntryKind::Dir => 0,
+        EntryKind::File => 1,
+        EntryKind::Symlink => 2,
+        EntryKind::Fifo => 3,
+        EntryKind::Socket => 4,
+        EntryKind::BlockDevice => 5,
+        EntryKind::CharDevice => 6,
+        EntryKind::Unknown => 7,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortSpec {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl From<SortKey> for SortSpec {
+    fn from(key: SortKey) -> Self {
+        SortSpec {
+            key,
+            descending: false,
+        }
+    }
+}
+
+impl FromStr for SortSpec {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        let (key, descending) = match value.rsplit_once(':') {
+            Some((key, "asc")) => (key, false),
+            Some((key, "desc")) => (key, true),
+            Some((_, direction)) => {
+                return Err(format!(
+                    "invalid sort direction '{}': expected asc or desc",
+                    direction
+                ))
+            }
+            None => (value, false),
+        };
+
+        let key = SortKey::from_str(key, false)
+            .or_else(|_| SortKey::from_str(key, true))
+            .map_err(|_| {
+                let keys = SortKey::value_variants()
+                    .iter()
+                    .filter_map(|key| key.to_possible_value())
+                    .flat_map(|value| value.get_name_and_aliases().nth(1).map(str::to_string))
+                    .collect::<Vec<_>>();
+                format!(
+                    "invalid sort key '{}': expected one of {}",
+                    key,
+                    keys.join(", ")
+                )
+            })?;
+
+        Ok(SortSpec { key, descending })
+    }
+}
+
+impl Display for SortSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.descending {
+            true => write!(f, "{}:desc", self.key),
+            false => write!(f, "{}", self.key),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GroupDirs {
+    First,
+    Last,
+    None,
+}
+
+//$[begin_cov_exclude]
+impl ValueEnum for GroupDirs {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[GroupDirs::First, GroupDirs::Last, GroupDirs::None]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            GroupDirs::First => Some(PossibleValue::new("first")),
+            GroupDirs::Last => Some(PossibleValue::new("last")),
+            GroupDirs::None => Some(PossibleValue::new("none")),
+        }
+    }
+}
+//$[end_cov_exclude]
+
+impl Display for GroupDirs {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            GroupDirs::First => "first",
+            GroupDirs::Last => "last",
+            GroupDirs::None => "none",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl GroupDirs {
+    fn rank(&self, entry: &Entry) -> u8 {
+        let is_dir = entry.kind == EntryKind::Dir;
+
+        match self {
+            GroupDirs::First => u8::from(!is_dir),
+            GroupDirs::Last => u8::from(is_dir),
+            GroupDirs::None => 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SortValue {
+    Text(String),
+    Bytes(Vec<u8>),
+    Natural(String),
+    Time(SystemTime),
+    Number(u64),
+    Missing,
+}
+
+impl From<Option<SystemTime>> for SortValue {
+    fn from(time: Option<SystemTime>) -> Self {
+        time.map_or(SortValue::Missing, SortValue::Time)
+    }
+}
+
+impl Ord for SortValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortValue::Bytes(a), SortValue::Bytes(b)) => a.cmp(b),
+            (SortValue::Natural(a), SortValue::Natural(b)) => natural_cmp(a, b),
+            (SortValue::Time(a), SortValue::Time(b)) => a.cmp(b),
+            (SortValue::Number(a), SortValue::Number(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for SortValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(PartialEq, Eq)]
+struct Directed(SortValue, bool);
+
+impl Ord for Directed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.0, &other.0, self.1) {
+            (SortValue::Missing, SortValue::Missing, _) => Ordering::Equal,
+            (SortValue::Missing, _, _) => Ordering::Greater,
+            (_, SortValue::Missing, _) => Ordering::Less,
+            (_, _, true) => other.0.cmp(&self.0),
+            (_, _, false) => self.0.cmp(&other.0),
+        }
+    }
+}
+
+impl PartialOrd for Directed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub(crate) struct SortOptions {
+    pub(crate) reverse: bool,
+    pub(crate) sort_by: Vec<SortSpec>,
+    pub(crate) group_dirs: GroupDirs,
+}
+
+impl From<&Args> for SortOptions {
+    fn from(item: &Args) -> Self {
+        SortOptions {
+            reverse: item.reverse,
+            sort_by: item.sort_by.clone(),
+            group_dirs: item.group_dirs.clone(),
+        }
+    }
+}
+
+impl SortOptions {
+    pub(crate) fn sort(&self, entries: &mut [Entry]) {
+        entries.sort_by_cached_key(|entry| {
+            let mut values = self
+                .sort_by
+                .iter()
+                .map(|spec| Directed(spec.key.value(entry), spec.descending != self.reverse))
+                .collect::<Vec<_>>();
+            values.push(Directed(SortValue::Text(entry.name.clone()), self.reverse));
+
+            (self.group_dirs.rank(entry), values)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::tests::entry;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn sorted_names(args: Args, mut entries: Vec<Entry>) -> Vec<String> {
+        SortOptions::from(&args).sort(&mut entries);
+        entries.into_iter().map(|entry| entry.name).collect()
+    }
+
+    fn copy(source: &Entry) -> Entry {
+        let mut copy = entry(&source.name, source.kind, vec![]);
+        copy.inode = source.inode;
+        copy.nlink = source.nlink;
+        copy.atime = source.atime;
+        copy.ctime = source.ctime;
+        copy
+    }
+
+    fn sized(name: &str, kind: EntryKind, size: u64) -> Entry {
+        let mut entry = entry(name, kind, vec![]);
+        entry.size = size;
+        entry
+    }
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            sized("b", EntryKind::File, 10),
+            sized("d", EntryKind::Dir, 10),
+            sized("a", EntryKind::File, 20),
+            sized("c", EntryKind::Dir, 30),
+            sized("e", EntryKind::File, 10),
+        ]
+    }
+
+    #[test]
+    fn it_should_create_sort_options() {
+        let sort_options = SortOptions::from(&Args::default());
+
+        assert!(!sort_options.reverse);
+        assert_eq!(sort_options.sort_by, vec![SortSpec::from(SortKey::Name)]);
+        assert_eq!(sort_options.group_dirs, GroupDirs::None);
+    }
+
+    #[test]
+    fn it_should_parse_sort_keys() {
+        assert_eq!("n", SortKey::Name.to_string());
+        assert_eq!("i", SortKey::CaseInsensitive.to_string());
+        assert_eq!("l", SortKey::Locale.to_string());
+        assert_eq!("v", SortKey::Natural.to_string());
+        assert_eq!("x", SortKey::Extension.to_string());
+        assert_eq!("k", SortKey::Kind.to_string());
+        assert_eq!("b", SortKey::Created.to_string());
+        assert_eq!("c", SortKey::Changed.to_string());
+        assert_eq!("m", SortKey::Modified.to_string());
+        assert_eq!("a", SortKey::Accessed.to_string());
+        assert_eq!("o", SortKey::Owner.to_string());
+        assert_eq!("s", SortKey::Size.to_string());
+        assert_eq!("I", SortKey::Inode.to_string());
+        assert_eq!("h", SortKey::Links.to_string());
+    }
+
+    #[test]
+    fn it_should_parse_sort_specs() {
+        assert_eq!("s".parse(), Ok(SortSpec::from(SortKey::Size)));
+        assert_eq!("size".parse(), Ok(SortSpec::from(SortKey::Size)));
+        assert_eq!("Size:asc".parse(), Ok(SortSpec::from(SortKey::Size)));
+        assert_eq!("i".parse(), Ok(SortSpec::from(SortKey::CaseInsensitive)));
+        assert_eq!("I".parse(), Ok(SortSpec::from(SortKey::Inode)));
+        assert_eq!("INODE".parse(), Ok(SortSpec::from(SortKey::Inode)));
+        assert_eq!(
+            "mtime:desc".parse(),
+            Ok(SortSpec {
+                key: SortKey::Modified,
+                descending: true,
+            })
+        );
+        assert_eq!(
+            "size:up".parse::<SortSpec>(),
+            Err(String::from(
+                "invalid sort direction 'up': expected asc or desc"
+            ))
+        );
+        assert!("bogus"
+            .parse::<SortSpec>()
+            .unwrap_err()
+            .starts_with("invalid sort key 'bogus': expected one of name, iname,"));
+        assert_eq!(
+            SortSpec {
+                key: SortKey::Size,
+                descending: true,
+            }
+            .to_string(),
+            "s:desc"
+        );
+    }
+
+    #[test]
+    fn it_should_sort_by_several_keys() {
+        let args = Args {
+            sort_by: vec![
+                SortKey::Kind.into(),
+                SortSpec {
+                    key: SortKey::Size,
+                    descending: true,
+                },
+            ],
+            ..Args::default()
+        };
+
+        assert_eq!(sorted_names(args, entries()), vec!["c", "d", "a", "b", "e"]);
+    }
+
+    #[test]
+    fn it_should_sort_by_extension_and_metadata() {
+        let mut entries = [
+            entry("b.txt", EntryKind::File, vec![]),
+            entry("a.rs", EntryKind::File, vec![]),
+            entry("Makefile", EntryKind::File, vec![]),
+            entry(".hidden", EntryKind::File, vec![]),
+            entry("c.rs", EntryKind::File, vec![]),
+        ];
+        for (i, entry) in entries.iter_mut().enumerate() {
+            entry.inode = 10 - i as u64;
+            entry.nlink = (i % 2) as u64 + 1;
+            entry.atime = Some(UNIX_EPOCH + Duration::from_secs(i as u64));
+            entry.ctime = Some(UNIX_EPOCH + Duration::from_secs(5 - i as u64));
+        }
+
+        let sorted = |key: SortKey| {
+            let args = Args {
+                sort_by: vec![key.into()],
+                ..Args::default()
+            };
+            let entries = entries.iter().map(copy).collect();
+            sorted_names(args, entries)
+        };
+
+        assert_eq!(
+            sorted(SortKey::Extension),
+            vec![".hidden", "Makefile", "a.rs", "c.rs", "b.txt"]
+        );
+        assert_eq!(
+            sorted(SortKey::Inode),
+            vec!["c.rs", ".hidden", "Makefile", "a.rs", "b.txt"]
+        );
+        assert_eq!(
+            sorted(SortKey::Links),
+            vec!["Makefile", "b.txt", "c.rs", ".hidden", "a.rs"]
+        );
+        assert_eq!(
+            sorted(SortKey::Accessed),
+            vec!["b.txt", "a.rs", "Makefile", ".hidden", "c.rs"]
+        );
+        assert_eq!(
+            sorted(SortKey::Changed),
+            vec!["c.rs", ".hidden", "Makefile", "a.rs", "b.txt"]
+        );
+    }
+
+    #[test]
+    fn it_should_sort_entries_without_birth_time_last() {
+        let born = |name, seconds| {
+            let mut entry = entry(name, EntryKind::File, vec![]);
+            entry.btime = Some(UNIX_EPOCH + Duration::from_secs(seconds));
+            entry
+        };
+        let entries = || {
+            let mut unknown = entry("unknown", EntryKind::File, vec![]);
+            unknown.ctime = Some(UNIX_EPOCH);
+            vec![born("late", 20), unknown, born("early", 10)]
+        };
+
+        let args = Args {
+            sort_by: vec![SortKey::Created.into()],
+            ..Args::default()
+        };
+        assert_eq!(
+            sorted_names(args, entries()),
+            vec!["early", "late", "unknown"]
+        );
+
+        let args = Args {
+            sort_by: vec![SortKey::Created.into()],
+            reverse: true,
+            ..Args::default()
+        };
+        assert_eq!(
+            sorted_names(args, entries()),
+            vec!["late", "early", "unknown"]
+        );
+    }
+
+    #[test]
+    fn it_should_reverse_every_key() {
+        let args = Args {
+            sort_by: vec![SortKey::Size.into()],
+            reverse: true,
+            ..Args::default()
+        };
+
+        assert_eq!(sorted_names(args, entries()), vec!["c", "a", "e", "d", "b"]);
+    }
+
+    #[test]
+    fn it_should_group_directories() {
+        let args = Args {
+            group_dirs: GroupDirs::First,
+            reverse: true,
+            ..Args::default()
+        };
+
+        assert_eq!(sorted_names(args, entries()), vec!["d", "c", "e", "b", "a"]);
+
+        let args = Args {
+            group_dirs: GroupDirs::Last,
+            ..Args::default()
+        };
+
+        assert_eq!(sorted_names(args, entries()), vec!["a", "b", "e", "c", "d"]);
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use crate::entries_handler::{DirId, EntriesHandler};
+use crate::entry::{Entry, EntryKind};
+use crate::git_ignore::GitIgnore;
+
+pub struct WalkEntry {
+    pub entry: Entry,
+    pub depth: usize,
+    pub is_last: bool,
+}
+
+struct Frame {
+    entries: VecDeque<(Entry, Option<bool>)>,
+    relative_dir: PathBuf,
+    git_ignore: Option<GitIgnore>,
+    id: Option<DirId>,
+}
+
+impl Frame {
+    fn new(
+        entries: Vec<Entry>,
+        relative_dir: PathBuf,
+        git_ignore: Option<GitIgnore>,
+        id: Option<DirId>,
+    ) -> Self {
+        Frame {
+            entries: entries.into_iter().map(|entry| (entry, None)).collect(),
+            relative_dir,
+            git_ignore,
+            id,
+        }
+    }
+}
+
+pub struct Walk<'a> {
+    entries_handler: &'a EntriesHandler,
+    frames: Vec<Frame>,
+    is_lazy: bool,
+    is_filtered: bool,
+}
+
+impl<'a> Walk<'a> {
+    pub(crate) fn lazy(
+        entries_handler: &'a EntriesHandler,
+        entries: Vec<Entry>,
+        git_ignore: Option<GitIgnore>,
+        id: Option<DirId>,
+    ) -> Self {
+        Walk {
+            entries_handler,
+            frames: vec![Frame::new(entries, PathBuf::new(), git_ignore, id)],
+            is_lazy: true,
+            is_filtered: entries_handler.has_match_filters(),
+        }
+    }
+
+    pub(crate) fn materialized(entries_handler: &'a EntriesHandler, entries: Vec<Entry>) -> Self {
+        Walk {
+            is_lazy: false,
+            is_filtered: false,
+            ..Walk::lazy(entries_handler, entries, None, None)
+        }
+    }
+
+    fn ancestors(&self) -> Vec<DirId> {
+        self.frames.iter().filter_map(|frame| frame.id).collect()
+    }
+
+    fn is_kept(&self, entry: &mut Entry, level: u64) -> bool {
+        let Some(frame) = self.frames.last() else {
+            return false;
+        };
+
+        self.entries_handler.is_kept(
+            entry,
+            &frame.relative_dir,
+            frame.git_ignore.as_ref(),
+            &self.ancestors(),
+            level,
+        )
+    }
+
+    fn has_kept_sibling(&mut self, level: u64) -> bool {
+        let ancestors = self.ancestors();
+        let entries_handler = self.entries_handler;
+        let Some(Frame {
+            entries,
+            relative_dir,
+            git_ignore,
+            ..
+        }) = self.frames.last_mut()
+        else {
+            return false;
+        };
+
+        entries.iter_mut().any(|(entry, is_kept)| {
+            *is_kept.get_or_insert_with(|| {
+                entries_handler.is_kept(entry, relative_dir, git_ignore.as_ref(), &ancestors, level)
+            })
+        })
+    }
+
+    fn descend(&mut self, entry: &mut Entry, level: u64) {
+        if !self.is_lazy {
+            self.frames.push(Frame::new(
+                std::mem::take(&mut entry.children),
+                PathBuf::new(),
+                None,
+                None,
+            ));
+            return;
+        }
+
+        let Some(frame) = self.frames.last() else {
+            return;
+        };
+        if entry.kind != EntryKind::Dir {
+            return;
+        }
+
+        let child_dir =
+            self.entries_handler
+                .child_dir(entry, &frame.relative_dir, frame.git_ignore.as_ref());
+        let entries = if entry.children.is_empty() {
+            self.entries_handler
+                .expand(entry, &child_dir, &self.ancestors(), level)
+        } else {
+            Some(std::mem::take(&mut entry.children))
+        };
+
+        if let Some(entries) = entries {
+            self.frames.push(Frame::new(
+                entries,
+                child_dir.relative_dir,
+                child_dir.git_ignore,
+                Some(child_dir.id),
+            ));
+        }
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = WalkEntry;
+
+    fn next(&mut self) -> Option<WalkEntry> {
+        loop {
+            let depth = self.frames.len().checked_sub(1)?;
+            let level = depth as u64 + 1;
+            let frame = self.frames.last_mut()?;
+            let Some((mut entry, is_kept)) = frame.entries.pop_front() else {
+                self.frames.pop();
+                continue;
+            };
+
+            let is_last = if self.is_filtered {
+                if !is_kept.unwrap_or_else(|| self.is_kept(&mut entry, level)) {
+                    continue;
+                }
+                !self.has_kept_sibling(level)
+            } else {
+                frame.entries.is_empty()
+            };
+
+            self.descend(&mut entry, level);
+
+            return Some(WalkEntry {
+                entry,
+                depth,
+                is_last,
+            });
+        }
+    }
+}
+
+pub(crate) fn collect_tree(entries: &mut dyn Iterator<Item = WalkEntry>) -> Vec<Entry> {
+    let mut levels: Vec<Vec<Entry>> = vec![vec![]];
+
+    for walk_entry in entries {
+        while levels.len() > walk_entry.depth + 1 {
+            close_level(&mut levels);
+        }
+        while levels.len() < walk_entry.depth + 1 {
+            levels.push(vec![]);
+        }
+        levels[walk_entry.depth].push(walk_entry.entry);
+    }
+
+    while levels.len() > 1 {
+        close_level(&mut levels);
+    }
+
+    levels.pop().unwrap_or_default()
+}
+
+fn close_level(levels: &mut Vec<Vec<Entry>>) {
+    let children = levels.pop().unwrap_or_default();
+
+    if let Some(parent) = levels.last_mut().and_then(|level| level.last_mut()) {
+        parent.children = children;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use assert_fs::prelude::*;
+
+    fn tree() -> assert_fs::TempDir {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("a/b/c").touch().unwrap();
+        temp.child("a/d").touch().unwrap();
+        temp.child("e").touch().unwrap();
+        temp
+    }
+
+    fn summary(walk: Walk) -> Vec<(String, usize, bool, Option<usize>)> {
+        walk.map(|walk_entry| {
+            (
+                walk_entry.entry.name,
+                walk_entry.depth,
+                walk_entry.is_last,
+                walk_entry.entry.truncated,
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn it_should_walk_depth_first() {
+        let temp = tree();
+        let entries_handler = EntriesHandler::new(&Args::default()).unwrap();
+
+        assert_eq!(
+            summary(entries_handler.walk(temp.path()).unwrap()),
+            vec![
+                (String::from("a"), 0, false, None),
+                (String::from("b"), 1, false, None),
+                (String::from("c"), 2, true, None),
+                (String::from("d"), 1, true, None),
+                (String::from("e"), 0, true, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_stop_walking_at_max_depth() {
+        let temp = tree();
+        let args = Args {
+            depth: Some(2),
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+
+        assert_eq!(
+            summary(entries_handler.walk(temp.path()).unwrap()),
+            vec![
+                (String::from("a"), 0, false, None),
+                (String::from("b"), 1, false, Some(1)),
+                (String::from("d"), 1, true, None),
+                (String::from("e"), 0, true, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_stream_a_walk_with_match_filters() {
+        let temp = tree();
+        let args = Args {
+            include: vec![String::from("c")],
+            ..Args::default()
+        };
+        let entries_handler = EntriesHandler::new(&args).unwrap();
+
+        assert_eq!(
+            summary(entries_handler.walk(temp.path()).unwrap()),
+            vec![
+                (String::from("a"), 0, true, None),
+                (String::from("b"), 1, true, None),
+                (String::from("c"), 2, true, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_stream_the_same_entries_as_a_materialized_tree() {
+        let temp = tree();
+        temp.child("a/f/g/h.rs").touch().unwrap();
+        temp.child("a/f/i").touch().unwrap();
+        temp.child("j/k").create_dir_all().unwrap();
+        temp.child("l.rs").write_str("1234").unwrap();
+
+        let filters = [
+            (vec!["**/*.rs"], None, None),
+            (vec!["d", "a/f/i"], None, None),
+            (vec!["**/*.rs"], Some(2), None),
+            (vec!["c"], Some(3), None),
+            (vec![], None, Some(1)),
+        ];
+
+        for (include, depth, min_size) in filters {
+            let args = Args {
+                include: include.into_iter().map(String::from).collect(),
+                depth,
+                min_size,
+                ..Args::default()
+            };
+            let entries_handler = EntriesHandler::new(&args).unwrap();
+            let materialized = Walk::materialized(
+                &entries_handler,
+                entries_handler.get_entries_recursive(temp.path()).unwrap(),
+            );
+
+            assert_eq!(
+                summary(entries_handler.walk(temp.path()).unwrap()),
+                summary(materialized),
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_collect_a_walk_into_a_tree() {
+        let temp = tree();
+        let entries_handler = EntriesHandler::new(&Args::default()).unwrap();
+
+        let entries = collect_tree(&mut entries_handler.walk(temp.path()).unwrap());
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].children.len(), 2);
+        assert_eq!(entries[0].children[0].children[0].name, "c");
+        assert!(entries[1].children.is_empty());
+    }
+}